@@ -0,0 +1,38 @@
+//! {{title}}
+advent_of_code::solution!({{day}}, parse);
+
+pub struct Grid {
+    width: usize,
+    height: usize,
+    cells: Vec<u8>,
+}
+
+impl Grid {
+    fn get(&self, x: usize, y: usize) -> Option<u8> {
+        (x < self.width && y < self.height).then(|| self.cells[y * self.width + x])
+    }
+}
+
+pub fn parse(input: &str) -> Grid {
+    let lines: Vec<&[u8]> = input.lines().map(str::as_bytes).collect();
+    let width = lines.first().map_or(0, |line| line.len());
+    assert!(
+        lines.iter().all(|line| line.len() == width),
+        "all rows of the grid must have the same width"
+    );
+
+    Grid {
+        width,
+        height: lines.len(),
+        cells: lines.concat(),
+    }
+}
+
+pub fn part_one(grid: &Grid) -> Option<{{answer_type}}> {
+    let _start = grid.get(0, 0);
+    None
+}
+
+pub fn part_two(grid: &Grid) -> Option<{{answer_type}}> {
+    None
+}
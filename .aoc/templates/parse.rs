@@ -0,0 +1,20 @@
+//! {{title}}
+advent_of_code::solution!({{day}}, parse);
+
+pub struct Input {
+    lines: Vec<String>,
+}
+
+pub fn parse(input: &str) -> Input {
+    Input {
+        lines: input.lines().map(String::from).collect(),
+    }
+}
+
+pub fn part_one(input: &Input) -> Option<{{answer_type}}> {
+    None
+}
+
+pub fn part_two(input: &Input) -> Option<{{answer_type}}> {
+    None
+}
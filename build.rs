@@ -0,0 +1,172 @@
+/// Generates the solution registry that is compiled into the main binary.
+/// Every scaffolded `src/bin/YYYY-NN.rs` is included as a module so `cargo all` can run it in-process.
+/// Also generates one test per entry of each day's example manifest `data/YYYY/examples/NN.toml`, which the `solution!` macro includes.
+use std::{collections::HashSet, env, fs, path::Path};
+
+fn main() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let bin_dir = Path::new(&manifest_dir).join("src").join("bin");
+    let data_dir = Path::new(&manifest_dir).join("data");
+    let out_dir = env::var("OUT_DIR").unwrap();
+
+    println!("cargo:rerun-if-changed={}", bin_dir.display());
+
+    // (year, day), e.g. ("2023", "01") for `src/bin/2023-01.rs`.
+    let mut days: Vec<(String, String)> = fs::read_dir(&bin_dir)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .filter_map(|entry| {
+                    let name = entry.file_name().into_string().ok()?;
+                    let (year, day) = name.strip_suffix(".rs")?.split_once('-')?;
+                    let is_year = year.len() == 4 && year.parse::<u16>().is_ok_and(|y| y >= 2015);
+                    let is_day =
+                        day.len() == 2 && day.parse::<u8>().is_ok_and(|d| (1..=25).contains(&d));
+                    (is_year && is_day).then(|| (year.to_string(), day.to_string()))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    days.sort();
+
+    let years: HashSet<&String> = days.iter().map(|(year, _)| year).collect();
+    for year in years {
+        let examples_dir = data_dir.join(year).join("examples");
+        println!("cargo:rerun-if-changed={}", examples_dir.display());
+    }
+
+    let mut modules = String::new();
+    let mut entries = String::new();
+
+    for (year, day) in &days {
+        let path = bin_dir.join(format!("{year}-{day}.rs"));
+        // solution tests already run as part of each bin, skip them when testing the main binary.
+        modules.push_str(&format!(
+            "#[cfg(not(test))]\n#[allow(dead_code, unused_imports)]\n#[path = {:?}]\nmod day_{year}_{day};\n",
+            path.display().to_string()
+        ));
+        entries.push_str(&format!(
+            "    (advent_of_code::year!({year}), day_{year}_{day}::SOLUTION),\n"
+        ));
+    }
+
+    let registry = format!(
+        "// @generated by build.rs\n\
+        {modules}\n\
+        #[cfg(not(test))]\n\
+        pub const REGISTRY: advent_of_code::template::registry::Registry =\n    \
+            advent_of_code::template::registry::Registry::new(&[\n{entries}]);\n\n\
+        #[cfg(test)]\n\
+        pub const REGISTRY: advent_of_code::template::registry::Registry =\n    \
+            advent_of_code::template::registry::Registry::new(&[]);\n"
+    );
+
+    let out_path = Path::new(&out_dir).join("solutions.rs");
+    fs::write(out_path, registry).unwrap();
+
+    let tests_dir = Path::new(&out_dir).join("examples");
+    fs::create_dir_all(&tests_dir).unwrap();
+
+    for (year, day) in &days {
+        let manifest_path = data_dir
+            .join(year)
+            .join("examples")
+            .join(format!("{day}.toml"));
+        let tests = match fs::read_to_string(&manifest_path) {
+            Ok(manifest) => example_tests(&manifest)
+                .unwrap_or_else(|e| panic!("invalid manifest {}: {e}", manifest_path.display())),
+            Err(_) => String::new(),
+        };
+
+        fs::write(tests_dir.join(format!("{year}-{day}.rs")), tests).unwrap();
+    }
+}
+
+/// Generates a test for every `[[example]]` entry of a manifest, e.g.
+///
+/// ```toml
+/// [[example]]
+/// file = "08-1.txt"
+/// part = 1
+/// answer = 2
+/// ```
+///
+/// An entry may override parameters of the solution, e.g. `params = { expansion = 10 }`.
+fn example_tests(manifest: &str) -> Result<String, String> {
+    let manifest: toml::Table = manifest.parse().map_err(|e| format!("{e}"))?;
+
+    let examples = match manifest.get("example") {
+        Some(toml::Value::Array(examples)) => examples.as_slice(),
+        Some(_) => return Err("`example` must be an array of tables".into()),
+        None => &[],
+    };
+
+    let mut names = HashSet::new();
+    let mut tests = String::from("// @generated by build.rs\n");
+
+    for (i, example) in examples.iter().enumerate() {
+        let entry = i + 1;
+        let field = |key: &str| {
+            example
+                .get(key)
+                .ok_or_else(|| format!("example {entry} is missing `{key}`"))
+        };
+
+        let file = field("file")?
+            .as_str()
+            .ok_or_else(|| format!("`file` of example {entry} must be a string"))?;
+
+        let part = match field("part")?.as_integer() {
+            Some(1) => "one",
+            Some(2) => "two",
+            _ => return Err(format!("`part` of example {entry} must be 1 or 2")),
+        };
+
+        let answer = match field("answer")? {
+            toml::Value::Integer(answer) => answer.to_string(),
+            toml::Value::String(answer) => answer.clone(),
+            _ => {
+                return Err(format!(
+                    "`answer` of example {entry} must be an integer or a string"
+                ))
+            }
+        };
+
+        let params = match example.get("params") {
+            Some(toml::Value::Table(params)) => params
+                .iter()
+                .map(|(name, value)| match value {
+                    toml::Value::String(value) => Ok((name.clone(), value.clone())),
+                    toml::Value::Integer(_) | toml::Value::Float(_) | toml::Value::Boolean(_) => {
+                        Ok((name.clone(), value.to_string()))
+                    }
+                    _ => Err(format!(
+                        "parameter `{name}` of example {entry} must be a string, number or boolean"
+                    )),
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            Some(_) => return Err(format!("`params` of example {entry} must be a table")),
+            None => vec![],
+        };
+
+        let stem: String = file
+            .trim_end_matches(".txt")
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+
+        let mut name = format!("part_{part}_{stem}");
+        if !names.insert(name.clone()) {
+            name = format!("{name}_{entry}");
+            names.insert(name.clone());
+        }
+
+        tests.push_str(&format!(
+            "\n#[test]\nfn {name}() {{\n    check({}, {file:?}, {answer:?}, &{params:?});\n}}\n",
+            if part == "one" { 1 } else { 2 }
+        ));
+    }
+
+    Ok(tests)
+}
@@ -63,7 +63,7 @@ where
     I: Iterator<Item = char>,
 {
     for c in chars {
-        if c.is_digit(10) {
+        if c.is_ascii_digit() {
             return c.to_digit(10);
         }
     }
@@ -123,22 +123,6 @@ fn parse_digit(text: &str) -> Option<u32> {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file_part(
-            "examples", DAY, 1,
-        ));
-        assert_eq!(result, Some(142));
-    }
-
-    #[test]
-    fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file_part(
-            "examples", DAY, 2,
-        ));
-        assert_eq!(result, Some(281));
-    }
-
     #[test]
     fn test_parse_digits() {
         assert_eq!(parse_digit("one"), Some(1));
@@ -2,9 +2,14 @@
 
 advent_of_code::solution!(2);
 
+advent_of_code::param!(MAX_RED: u32 = 12);
+advent_of_code::param!(MAX_GREEN: u32 = 13);
+advent_of_code::param!(MAX_BLUE: u32 = 14);
+
 pub fn part_one(input: &str) -> Option<u32> {
+    let (max_red, max_green, max_blue) = (MAX_RED.get(), MAX_GREEN.get(), MAX_BLUE.get());
     let games = input.lines().map(parse_line).collect::<Option<Vec<Game>>>().unwrap();
-    Some(games.iter().filter(|g| g.is_valid(12,13,14)).map(|g| g.id).sum())
+    Some(games.iter().filter(|g| g.is_valid(max_red, max_green, max_blue)).map(|g| g.id).sum())
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
@@ -88,9 +93,7 @@ impl Reveal {
 fn parse_line(line: &str) -> Option<Game> {
     let mut parts = line.split(":");
     let id_part = parts.next().unwrap().split(" ").nth(1);
-    if id_part.is_none() {
-        return None;
-    }
+    id_part?;
     let id = id_part.unwrap().parse::<u32>();
     if id.is_err() {
         println!("id is err");
@@ -101,9 +104,7 @@ fn parse_line(line: &str) -> Option<Game> {
     let mut reveals = Vec::new();
     for reveal in reveals_str.split(";") {
         let reveal = Reveal::from_str(reveal);
-        if reveal.is_none() {
-            return None;
-        }
+        reveal.as_ref()?;
         reveals.push(reveal.unwrap());
     }
     Some(Game{
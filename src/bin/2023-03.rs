@@ -1,14 +1,16 @@
 use std::collections::HashSet;
 
-advent_of_code::solution!(3);
+advent_of_code::solution!(3, parse);
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let engine = Engine::from_str(input);
+pub fn parse(input: &str) -> Engine {
+    Engine::from_str(input)
+}
+
+pub fn part_one(engine: &Engine) -> Option<u32> {
     Some(engine.get_symbol_adjacent_numbers().iter().sum())
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let engine = Engine::from_str(input);
+pub fn part_two(engine: &Engine) -> Option<u32> {
     let gears = engine.get_gears();
     let ratios = gears.iter().map(|(_, [a, b])| *a * *b).collect::<Vec<_>>();
     Some(ratios.iter().sum())
@@ -53,7 +55,7 @@ impl Pos {
 }
 
 #[derive(Debug)]
-struct Engine {
+pub struct Engine {
     parts: Vec<EnginePart>,
 }
 
@@ -75,7 +77,7 @@ impl Engine {
                     if c == '.' {
                         continue;
                     }
-                    if c.is_digit(10) {
+                    if c.is_ascii_digit() {
                         in_number = true;
                         cur_number = c.to_digit(10).unwrap();
                         cur_number_cols.push(col);
@@ -86,7 +88,7 @@ impl Engine {
                         pos: Pos { row, col },
                     });
                 } else {
-                    if c.is_digit(10) {
+                    if c.is_ascii_digit() {
                         cur_number = cur_number * 10 + c.to_digit(10).unwrap();
                         cur_number_cols.push(col);
                         continue;
@@ -164,9 +166,9 @@ impl Engine {
         let mut result = Vec::new();
         for part in &self.parts {
             if let EnginePart::Symbol { value: '*', pos } = part {
-                let neighbours = &self.get_adjacent_numbers(pos.clone());
+                let neighbours = &self.get_adjacent_numbers(*pos);
                 if neighbours.len() == 2 {
-                    result.push((pos.clone(), [neighbours[0], neighbours[1]]));
+                    result.push((*pos, [neighbours[0], neighbours[1]]));
                 }
             }
         }
@@ -180,13 +182,15 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let engine = parse(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_one(&engine);
         assert_eq!(result, Some(4361));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let engine = parse(&advent_of_code::template::read_file("examples", DAY));
+        let result = part_two(&engine);
         assert_eq!(result, Some(467835));
     }
 }
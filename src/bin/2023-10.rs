@@ -16,7 +16,7 @@ pub fn part_two(input: &str) -> Option<u32> {
     let loop_ = get_loop(&pipes, start);
     let size = (pipes[0].len(), pipes.len());
     let outer = get_outer(&loop_, &pipes);
-    print_loop(&input, &loop_, &outer, size);
+    print_loop(input, &loop_, &outer, size);
     let area = (size.0) * (size.1);
     println!("{} {} {}", area, loop_.len(), outer.len());
     Some((area - loop_.len() - outer.len()) as u32)
@@ -37,7 +37,7 @@ impl std::fmt::Debug for Pipe {
     }
 }
 
-fn get_outer(loop_: &[(usize, usize)], pipes: &Vec<Vec<Pipe>>) -> HashSet<(usize, usize)> {
+fn get_outer(loop_: &[(usize, usize)], pipes: &[Vec<Pipe>]) -> HashSet<(usize, usize)> {
     let start = (0, 0);
     let size = (pipes[0].len(), pipes.len());
     let outer_verts = df_fill(pipes, start, size);
@@ -53,7 +53,7 @@ fn get_outer(loop_: &[(usize, usize)], pipes: &Vec<Vec<Pipe>>) -> HashSet<(usize
 }
 
 fn df_fill(
-    pipes: &Vec<Vec<Pipe>>,
+    pipes: &[Vec<Pipe>],
     start: (usize, usize),
     size: (usize, usize),
 ) -> Vec<(usize, usize)> {
@@ -73,7 +73,7 @@ fn df_fill(
 }
 
 fn get_neighbours(
-    pipes: &Vec<Vec<Pipe>>,
+    pipes: &[Vec<Pipe>],
     current: (usize, usize),
     size: (usize, usize),
 ) -> Vec<(usize, usize)> {
@@ -122,20 +122,17 @@ fn get_loop(pipes: &[Vec<Pipe>], start: (usize, usize)) -> Vec<(usize, usize)> {
     current = pipes[current.1][current.0].connections[0];
     while current != start {
         loop_.push(current);
-        let next = pipes[current.1][current.0]
+        let next = *pipes[current.1][current.0]
             .connections
-            .iter()
-            .filter(|(x, y)| *x != last.0 || *y != last.1)
-            .next()
-            .unwrap()
-            .clone();
+            .iter().find(|(x, y)| *x != last.0 || *y != last.1)
+            .unwrap();
         last = current;
         current = next;
     }
     loop_
 }
 
-fn trim_connections(pipes: &mut Vec<Vec<Pipe>>) {
+fn trim_connections(pipes: &mut [Vec<Pipe>]) {
     let (rows, cols) = (pipes.len(), pipes[0].len());
     for j in 0..rows {
         for i in 0..cols {
@@ -283,34 +280,3 @@ fn print_loop(
     }
     println!("{}", i_counter);
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file_part(
-            "examples", DAY, 1,
-        ));
-        assert_eq!(result, Some(4));
-
-        let result = part_one(&advent_of_code::template::read_file_part(
-            "examples", DAY, 2,
-        ));
-        assert_eq!(result, Some(8));
-    }
-
-    #[test]
-    fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file_part(
-            "examples", DAY, 3,
-        ));
-        assert_eq!(result, Some(4));
-
-        let result = part_two(&advent_of_code::template::read_file_part(
-            "examples", DAY, 4,
-        ));
-        assert_eq!(result, Some(10));
-    }
-}
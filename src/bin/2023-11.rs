@@ -2,10 +2,7 @@ use std::collections::HashSet;
 
 advent_of_code::solution!(11);
 
-#[cfg(not(test))]
-const EXPANSION: usize = 1_000_000;
-#[cfg(test)]
-const EXPANSION: usize = 100;
+advent_of_code::param!(EXPANSION: usize = 1_000_000);
 
 
 pub fn part_one(input: &str) -> Option<u64> {
@@ -13,7 +10,7 @@ pub fn part_one(input: &str) -> Option<u64> {
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
-    solve(input, EXPANSION)
+    solve(input, EXPANSION.get())
 }
 
 fn solve(input: &str, expansion: usize) -> Option<u64> {
@@ -114,20 +111,3 @@ fn parse(input: &str) -> Sky {
         .map(|line| line.chars().map(|c| c == '#').collect())
         .collect())
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(374));
-    }
-
-    #[test]
-    fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(8410));
-    }
-}
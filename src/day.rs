@@ -114,6 +114,83 @@ impl Iterator for AllDays {
 
 /* -------------------------------------------------------------------------- */
 
+/// A selection of days, e.g. `3-7,11`. Days are sorted and unique.
+///
+/// ```
+/// # use advent_of_code::Days;
+/// let days: Days = "3-5,11,4".parse().unwrap();
+/// assert_eq!(days.to_string(), "03,04,05,11")
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Days(Vec<Day>);
+
+impl Days {
+    /// Every day of advent.
+    pub fn all() -> Self {
+        Self(all_days().collect())
+    }
+
+    pub fn contains(&self, day: Day) -> bool {
+        self.0.contains(&day)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Day> + '_ {
+        self.0.iter().copied()
+    }
+}
+
+impl Default for Days {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+impl Display for Days {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let days: Vec<String> = self.0.iter().map(Day::to_string).collect();
+        f.write_str(&days.join(","))
+    }
+}
+
+impl FromStr for Days {
+    type Err = DaysFromStrError;
+
+    /// Parses a comma-separated list of days and inclusive ranges of days.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut days = vec![];
+
+        for item in s.split(',').map(str::trim) {
+            let (first, last) = item.split_once('-').unwrap_or((item, item));
+            let first: Day = first.trim().parse().map_err(|_| DaysFromStrError)?;
+            let last: Day = last.trim().parse().map_err(|_| DaysFromStrError)?;
+
+            if first > last {
+                return Err(DaysFromStrError);
+            }
+
+            days.extend((first.0..=last.0).map(Day));
+        }
+
+        days.sort_unstable();
+        days.dedup();
+        Ok(Self(days))
+    }
+}
+
+/// An error which can be returned when parsing [`Days`].
+#[derive(Debug)]
+pub struct DaysFromStrError;
+
+impl Error for DaysFromStrError {}
+
+impl Display for DaysFromStrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("expecting days between 1 and 25, e.g. `3-7,11`")
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
 /// Creates a [`Day`] value in a const context.
 #[macro_export]
 macro_rules! day {
@@ -134,7 +211,20 @@ macro_rules! day {
 
 #[cfg(feature = "test_lib")]
 mod tests {
-    use super::{all_days, Day};
+    use super::{all_days, Day, Days};
+
+    #[test]
+    fn days_from_str() {
+        let days: Days = "3-5, 11,4".parse().unwrap();
+        assert_eq!(days.0, vec![Day(3), Day(4), Day(5), Day(11)]);
+        assert_eq!(days.to_string(), "03,04,05,11");
+        assert_eq!("25".parse::<Days>().unwrap().0, vec![Day(25)]);
+
+        assert!("7-3".parse::<Days>().is_err());
+        assert!("0-3".parse::<Days>().is_err());
+        assert!("3-".parse::<Days>().is_err());
+        assert!("".parse::<Days>().is_err());
+    }
 
     #[test]
     fn all_days_iterator() {
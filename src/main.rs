@@ -1,55 +1,154 @@
-use advent_of_code::template::commands::{all, download, read, scaffold, solve};
+use advent_of_code::template::{
+    commands::{all, download, examples, read, readme, scaffold, solve, status, verify, watch},
+    limits, runner,
+};
 use args::{parse, AppArguments};
+use std::env;
+
+/// Every scaffolded day, compiled into this binary so `all` can run them in-process.
+mod solutions {
+    include!(concat!(env!("OUT_DIR"), "/solutions.rs"));
+}
 
 mod args {
-    use std::process;
+    use std::{path::PathBuf, process, time::Duration};
 
-    use advent_of_code::Day;
+    use advent_of_code::{
+        template::{
+            baseline::CompareOptions,
+            benchmark::BenchConfig,
+            commands::{
+                all::{AllOptions, Filter},
+                scaffold,
+                solve::SolveOptions,
+            },
+            limits::Limits,
+            params::Override,
+            runner::{Format, RunOptions, MISSING_OUTPUT},
+        },
+        Day, Year,
+    };
 
     pub enum AppArguments {
         Download {
             day: Day,
         },
+        Examples {
+            day: Day,
+            overwrite: bool,
+            tests: bool,
+        },
         Read {
             day: Day,
         },
+        Readme {
+            dry_run: bool,
+        },
         Scaffold {
             day: Day,
+            template: String,
+            answer_type: String,
+            download: bool,
         },
         Solve {
             day: Day,
-            release: bool,
-            time: bool,
-            submit: Option<u8>,
+            options: RunOptions,
+            solve: SolveOptions,
         },
         All {
+            options: RunOptions,
+            output: Option<PathBuf>,
+            all: AllOptions,
+        },
+        Status {
+            format: Format,
+            output: Option<PathBuf>,
+            tests: bool,
+        },
+        Verify {
+            filter: Filter,
+        },
+        Watch {
+            day: Day,
             release: bool,
-            time: bool,
         },
     }
 
-    pub fn parse() -> Result<AppArguments, Box<dyn std::error::Error>> {
+    /// Parses the command and `--year`, which every command accepts.
+    pub fn parse() -> Result<(Option<Year>, AppArguments), Box<dyn std::error::Error>> {
         let mut args = pico_args::Arguments::from_env();
+        let subcommand = args.subcommand()?;
+        let year = args.opt_value_from_str("--year")?;
 
-        let app_args = match args.subcommand()?.as_deref() {
+        let app_args = match subcommand.as_deref() {
             Some("all") => AppArguments::All {
-                release: args.contains("--release"),
-                time: args.contains("--time"),
+                options: parse_run_options(&mut args)?,
+                output: args.opt_value_from_str("--output")?,
+                all: AllOptions {
+                    is_release: args.contains("--release"),
+                    compare: parse_compare_options(&mut args)?,
+                    jobs: args.opt_value_from_str("--jobs")?.unwrap_or(1),
+                    filter: parse_filter(&mut args)?,
+                },
             },
             Some("download") => AppArguments::Download {
                 day: args.free_from_str()?,
             },
-            Some("read") => AppArguments::Read {
+            Some("examples") => AppArguments::Examples {
                 day: args.free_from_str()?,
+                overwrite: args.contains("--overwrite"),
+                tests: args.contains("--tests"),
             },
-            Some("scaffold") => AppArguments::Scaffold {
+            Some("read") => AppArguments::Read {
                 day: args.free_from_str()?,
             },
+            Some("readme") => AppArguments::Readme {
+                dry_run: args.contains("--dry-run"),
+            },
+            Some("scaffold") => {
+                // `--result` is a shorthand for the built-in template of parts returning `Result`.
+                let result = args.contains("--result");
+                AppArguments::Scaffold {
+                    day: args.free_from_str()?,
+                    template: args
+                        .opt_value_from_str("--template")?
+                        .unwrap_or_else(|| if result { "result" } else { "default" }.to_string()),
+                    answer_type: args
+                        .opt_value_from_str("--answer-type")?
+                        .unwrap_or_else(|| scaffold::DEFAULT_ANSWER_TYPE.to_string()),
+                    download: args.contains("--download"),
+                }
+            }
             Some("solve") => AppArguments::Solve {
+                day: args.free_from_str()?,
+                solve: SolveOptions {
+                    is_release: args.contains("--release"),
+                    submit: args.opt_value_from_str("--submit")?,
+                    force: args.contains("--force"),
+                    output: args.opt_value_from_str("--output")?,
+                    inputs: args.values_from_str("--input")?,
+                },
+                options: RunOptions {
+                    verify: args.contains("--verify"),
+                    params: args
+                        .values_from_str::<_, Override>("--param")?
+                        .iter()
+                        .map(|o| (o.name.as_str(), o.value.as_str()))
+                        .collect(),
+                    ..parse_run_options(&mut args)?
+                },
+            },
+            Some("status") => AppArguments::Status {
+                format: args.opt_value_from_str("--format")?.unwrap_or_default(),
+                output: args.opt_value_from_str("--output")?,
+                tests: args.contains("--tests"),
+            },
+            Some("verify") => AppArguments::Verify {
+                filter: parse_filter(&mut args)?,
+            },
+            Some("watch") => AppArguments::Watch {
                 day: args.free_from_str()?,
                 release: args.contains("--release"),
-                submit: args.opt_value_from_str("--submit")?,
-                time: args.contains("--time"),
             },
             Some(x) => {
                 eprintln!("Unknown command: {x}");
@@ -61,32 +160,179 @@ mod args {
             }
         };
 
+        // records written to stdout would mix with whatever the solutions print themselves.
+        if let AppArguments::All {
+            options:
+                RunOptions {
+                    format: Format::Json,
+                    ..
+                },
+            output: None,
+            ..
+        }
+        | AppArguments::Solve {
+            options:
+                RunOptions {
+                    format: Format::Json,
+                    ..
+                },
+            solve: SolveOptions { output: None, .. },
+            ..
+        }
+        | AppArguments::Status {
+            format: Format::Json,
+            output: None,
+            ..
+        } = app_args
+        {
+            return Err(MISSING_OUTPUT.into());
+        }
+
         let remaining = args.finish();
         if !remaining.is_empty() {
             eprintln!("Warning: unknown argument(s): {remaining:?}.");
         }
 
-        Ok(app_args)
+        Ok((year, app_args))
+    }
+
+    /// Parses the options that `all` and `solve` pass on to the solutions.
+    fn parse_run_options(
+        args: &mut pico_args::Arguments,
+    ) -> Result<RunOptions, Box<dyn std::error::Error>> {
+        Ok(RunOptions {
+            is_timed: args.contains("--time"),
+            bench: parse_bench_config(args)?,
+            format: args.opt_value_from_str("--format")?.unwrap_or_default(),
+            limits: parse_limits(args)?,
+            ..RunOptions::default()
+        })
+    }
+
+    fn parse_bench_config(
+        args: &mut pico_args::Arguments,
+    ) -> Result<BenchConfig, Box<dyn std::error::Error>> {
+        let defaults = BenchConfig::default();
+
+        Ok(BenchConfig {
+            warmup_time: args
+                .opt_value_from_str("--warmup-time")?
+                .map_or(defaults.warmup_time, Duration::from_millis),
+            target_time: args
+                .opt_value_from_str("--bench-time")?
+                .map_or(defaults.target_time, Duration::from_millis),
+            iterations: args.opt_value_from_str("--bench-iterations")?,
+        })
+    }
+
+    fn parse_filter(args: &mut pico_args::Arguments) -> Result<Filter, Box<dyn std::error::Error>> {
+        Ok(Filter {
+            days: args.opt_value_from_str("--days")?.unwrap_or_default(),
+            only_unsolved: args.contains("--only-unsolved"),
+            only_failing: args.contains("--only-failing"),
+            part: args.opt_value_from_fn("--part", parse_part)?,
+        })
+    }
+
+    fn parse_part(s: &str) -> Result<u8, &'static str> {
+        match s {
+            "1" => Ok(1),
+            "2" => Ok(2),
+            _ => Err("expecting part 1 or 2"),
+        }
+    }
+
+    fn parse_limits(args: &mut pico_args::Arguments) -> Result<Limits, Box<dyn std::error::Error>> {
+        Ok(Limits {
+            timeout: args
+                .opt_value_from_str("--timeout")?
+                .map(Duration::from_millis),
+            memory_mb: args.opt_value_from_str("--memory-limit")?,
+        })
+    }
+
+    fn parse_compare_options(
+        args: &mut pico_args::Arguments,
+    ) -> Result<Option<CompareOptions>, Box<dyn std::error::Error>> {
+        if !args.contains("--compare") {
+            return Ok(None);
+        }
+
+        Ok(Some(CompareOptions {
+            commit: args.opt_value_from_str("--baseline")?,
+            threshold: args.opt_value_from_str("--threshold")?.unwrap_or(10.0),
+        }))
     }
 }
 
 fn main() {
+    // `all` runs days with limits in a child process of this binary, see `limits::run`.
+    if let Some(day) = limits::child_day() {
+        match solutions::REGISTRY.get(advent_of_code::template::year(), day) {
+            Some(solution) => limits::run_child(solution),
+            None => {
+                eprintln!("Day {day} is not scaffolded.");
+                std::process::exit(1);
+            }
+        }
+    }
+
     match parse() {
         Err(err) => {
             eprintln!("Error: {err}");
             std::process::exit(1);
         }
-        Ok(args) => match args {
-            AppArguments::All { release, time } => all::handle(release, time),
-            AppArguments::Download { day } => download::handle(day),
-            AppArguments::Read { day } => read::handle(day),
-            AppArguments::Scaffold { day } => scaffold::handle(day),
-            AppArguments::Solve {
-                day,
-                release,
-                time,
-                submit,
-            } => solve::handle(day, release, time, submit),
-        },
+        Ok((year, args)) => {
+            // the year is resolved from `AOC_YEAR` everywhere, including in spawned solution binaries.
+            if let Some(year) = year {
+                env::set_var("AOC_YEAR", year.to_string());
+            }
+
+            match args {
+                AppArguments::All {
+                    options,
+                    output,
+                    all,
+                } => {
+                    if let Some(output) = output {
+                        runner::set_output(&output);
+                    }
+
+                    all::handle(solutions::REGISTRY, options, all)
+                }
+                AppArguments::Download { day } => download::handle(day),
+                AppArguments::Examples {
+                    day,
+                    overwrite,
+                    tests,
+                } => examples::handle(day, overwrite, tests),
+                AppArguments::Read { day } => read::handle(day),
+                AppArguments::Readme { dry_run } => readme::handle(dry_run),
+                AppArguments::Scaffold {
+                    day,
+                    template,
+                    answer_type,
+                    download,
+                } => scaffold::handle(day, &template, &answer_type, download),
+                AppArguments::Solve {
+                    day,
+                    options,
+                    solve,
+                } => solve::handle(day, &options, &solve),
+                AppArguments::Status {
+                    format,
+                    output,
+                    tests,
+                } => {
+                    if let Some(output) = output {
+                        runner::set_output(&output);
+                    }
+
+                    status::handle(format, tests)
+                }
+                AppArguments::Verify { filter } => verify::handle(solutions::REGISTRY, &filter),
+                AppArguments::Watch { day, release } => watch::handle(day, release),
+            }
+        }
     };
 }
@@ -0,0 +1,103 @@
+//! Module that records accepted answers and verifies solution output against them.
+//! Answers are stored as `data/YYYY/answers/NN-P.txt`, one file per part.
+use std::{fmt::Display, fs, io, path::PathBuf};
+
+use crate::template::data_dir;
+use crate::Day;
+
+fn get_answer_path(day: Day, part: u8) -> PathBuf {
+    data_dir("answers").join(format!("{day}-{part}.txt"))
+}
+
+/// Reads the recorded answer for a part, [`None`] if no answer has been recorded yet.
+pub fn read(day: Day, part: u8) -> Option<String> {
+    let answer = fs::read_to_string(get_answer_path(day, part)).ok()?;
+    Some(answer.trim_end().to_string())
+}
+
+/// Records `answer` as the correct answer for a part.
+pub fn write(day: Day, part: u8, answer: &str) -> io::Result<()> {
+    let path = get_answer_path(day, part);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, format!("{answer}\n"))
+}
+
+/// Result of comparing a part's output with its recorded answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String },
+    Missing,
+}
+
+impl Verdict {
+    /// Compares `answer` with `expected`, ignoring trailing whitespace.
+    pub fn new(answer: Option<&str>, expected: Option<String>) -> Self {
+        match expected {
+            None => Verdict::Missing,
+            Some(expected) if answer.map(str::trim_end) == Some(expected.as_str()) => Verdict::Pass,
+            Some(expected) => Verdict::Fail { expected },
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Verdict::Pass => "pass",
+            Verdict::Fail { .. } => "fail",
+            Verdict::Missing => "missing",
+        }
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "✔ matches recorded answer"),
+            Verdict::Fail { expected } if expected.contains('\n') => {
+                write!(f, "✖ expected:\n{expected}")
+            }
+            Verdict::Fail { expected } => write!(f, "✖ expected {expected}"),
+            Verdict::Missing => write!(f, "? no recorded answer"),
+        }
+    }
+}
+
+/// Compares a part's output with its recorded answer.
+pub fn verify(day: Day, part: u8, answer: Option<&str>) -> Verdict {
+    Verdict::new(answer, read(day, part))
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Verdict;
+
+    #[test]
+    fn test_pass() {
+        assert_eq!(Verdict::new(Some("42"), Some("42".into())), Verdict::Pass);
+        assert_eq!(Verdict::new(Some("42\n"), Some("42".into())), Verdict::Pass);
+    }
+
+    #[test]
+    fn test_fail() {
+        assert_eq!(
+            Verdict::new(Some("41"), Some("42".into())),
+            Verdict::Fail {
+                expected: "42".into()
+            }
+        );
+        assert_eq!(
+            Verdict::new(None, Some("42".into())),
+            Verdict::Fail {
+                expected: "42".into()
+            }
+        );
+    }
+
+    #[test]
+    fn test_missing() {
+        assert_eq!(Verdict::new(Some("42"), None), Verdict::Missing);
+        assert_eq!(Verdict::new(None, None), Verdict::Missing);
+    }
+}
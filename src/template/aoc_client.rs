@@ -0,0 +1,373 @@
+//! Native client for the Advent of Code website.
+//! The session cookie is read from the `AOC_SESSION` or `ADVENT_OF_CODE_SESSION` environment variables,
+//! or from the file at `AOC_SESSION_FILE` (defaults to `~/.adventofcode.session`).
+use std::{env, fmt::Display, fs, io, path::PathBuf, time::Duration};
+
+use crate::template::{self, puzzle};
+use crate::{Day, Year};
+
+const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+#[derive(Debug)]
+pub enum AocClientError {
+    SessionNotFound,
+    YearNotSet,
+    Http(u16),
+    Transport(String),
+    IoError(io::Error),
+}
+
+impl Display for AocClientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AocClientError::SessionNotFound => write!(
+                f,
+                "no session cookie found. Set AOC_SESSION or create a \"~/.adventofcode.session\" file."
+            ),
+            AocClientError::YearNotSet => write!(f, "AOC_YEAR is not set, pass --year."),
+            AocClientError::Http(400) => write!(
+                f,
+                "the server rejected the request (400), the session cookie might have expired."
+            ),
+            AocClientError::Http(404) => write!(
+                f,
+                "the puzzle was not found (404), it might not be unlocked yet."
+            ),
+            AocClientError::Http(status) => write!(f, "the server responded with status {status}."),
+            AocClientError::Transport(e) => write!(f, "could not reach the server: {e}"),
+            AocClientError::IoError(e) => write!(f, "could not write output files to file system: {e}"),
+        }
+    }
+}
+
+impl From<io::Error> for AocClientError {
+    fn from(e: io::Error) -> Self {
+        AocClientError::IoError(e)
+    }
+}
+
+impl From<ureq::Error> for AocClientError {
+    fn from(e: ureq::Error) -> Self {
+        match e {
+            ureq::Error::Status(status, _) => AocClientError::Http(status),
+            ureq::Error::Transport(e) => AocClientError::Transport(e.to_string()),
+        }
+    }
+}
+
+pub struct AocClient {
+    base_url: String,
+    session: String,
+    year: Year,
+    agent: ureq::Agent,
+}
+
+impl AocClient {
+    pub fn new(base_url: &str, session: &str, year: Year) -> Self {
+        let agent = ureq::AgentBuilder::new()
+            .timeout(Duration::from_secs(30))
+            .user_agent(concat!(
+                env!("CARGO_PKG_NAME"),
+                "/",
+                env!("CARGO_PKG_VERSION")
+            ))
+            .build();
+
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.trim().to_string(),
+            year,
+            agent,
+        }
+    }
+
+    /// Creates a client from the environment. The base url can be overridden with `AOC_BASE_URL`, e.g. to test against a local server.
+    pub fn from_env() -> Result<Self, AocClientError> {
+        let base_url = env::var("AOC_BASE_URL").unwrap_or_else(|_| DEFAULT_BASE_URL.into());
+        let session = read_session().ok_or(AocClientError::SessionNotFound)?;
+        let year = template::try_year().ok_or(AocClientError::YearNotSet)?;
+        Ok(Self::new(&base_url, &session, year))
+    }
+
+    pub fn year(&self) -> Year {
+        self.year
+    }
+
+    /// Fetches the personal puzzle input for a day.
+    pub fn get_input(&self, day: Day) -> Result<String, AocClientError> {
+        self.get(&format!("{}/input", self.day_url(day)))
+    }
+
+    /// Fetches the puzzle page for a day and returns its description, i.e. the html of all `<article>` elements.
+    pub fn get_puzzle(&self, day: Day) -> Result<String, AocClientError> {
+        let html = self.get(&self.day_url(day))?;
+        Ok(extract_articles(&html))
+    }
+
+    /// Submits an answer and returns the html of the response message.
+    pub fn submit(&self, day: Day, part: u8, answer: &str) -> Result<String, AocClientError> {
+        let response = self
+            .agent
+            .post(&format!("{}/answer", self.day_url(day)))
+            .set("Cookie", &self.cookie())
+            .send_form(&[("level", &part.to_string()), ("answer", answer)])?;
+
+        Ok(extract_articles(&response.into_string()?))
+    }
+
+    fn get(&self, url: &str) -> Result<String, AocClientError> {
+        let response = self.agent.get(url).set("Cookie", &self.cookie()).call()?;
+        Ok(response.into_string()?)
+    }
+
+    fn day_url(&self, day: Day) -> String {
+        format!("{}/{}/day/{}", self.base_url, self.year, day.into_inner())
+    }
+
+    fn cookie(&self) -> String {
+        format!("session={}", self.session)
+    }
+}
+
+pub fn get_input_path(day: Day) -> String {
+    data_path("inputs", &format!("{day}.txt"))
+}
+
+pub fn get_puzzle_path(day: Day) -> String {
+    data_path("puzzles", &format!("{day}.md"))
+}
+
+/// Path of the puzzle description as served by the website, kept to extract examples from.
+pub fn get_puzzle_html_path(day: Day) -> String {
+    data_path("puzzles", &format!("{day}.html"))
+}
+
+fn data_path(folder: &str, file: &str) -> String {
+    template::data_dir(folder).join(file).display().to_string()
+}
+
+/// Downloads the input and puzzle description for a day to `data/YYYY/inputs` and `data/YYYY/puzzles`.
+/// The description is stored as html and converted to markdown.
+pub fn download(client: &AocClient, day: Day) -> Result<(), AocClientError> {
+    let puzzle_path = download_puzzle(client, day)?;
+    let input_path = download_input(client, day)?;
+
+    println!("🎄 Successfully wrote input to \"{}\".", &input_path);
+    println!("🎄 Successfully wrote puzzle to \"{}\".", &puzzle_path);
+    Ok(())
+}
+
+/// Downloads the input for a day to `data/YYYY/inputs` and returns its path.
+pub fn download_input(client: &AocClient, day: Day) -> Result<String, AocClientError> {
+    let input_path = get_input_path(day);
+    let input = client.get_input(day)?;
+
+    fs::create_dir_all(template::data_dir("inputs"))?;
+    fs::write(&input_path, input)?;
+    Ok(input_path)
+}
+
+/// Downloads the puzzle description for a day to `data/YYYY/puzzles` and returns the path of the markdown.
+pub fn download_puzzle(client: &AocClient, day: Day) -> Result<String, AocClientError> {
+    let puzzle_path = get_puzzle_path(day);
+    let puzzle = client.get_puzzle(day)?;
+
+    fs::create_dir_all(template::data_dir("puzzles"))?;
+    fs::write(get_puzzle_html_path(day), &puzzle)?;
+    fs::write(&puzzle_path, puzzle::to_markdown(&puzzle))?;
+    Ok(puzzle_path)
+}
+
+fn read_session() -> Option<String> {
+    let from_env = ["AOC_SESSION", "ADVENT_OF_CODE_SESSION"]
+        .iter()
+        .find_map(|key| env::var(key).ok());
+
+    let session = match from_env {
+        Some(session) => session,
+        None => fs::read_to_string(get_session_path()?).ok()?,
+    };
+
+    let session = session.trim().to_string();
+    (!session.is_empty()).then_some(session)
+}
+
+fn get_session_path() -> Option<PathBuf> {
+    if let Ok(path) = env::var("AOC_SESSION_FILE") {
+        return Some(PathBuf::from(path));
+    }
+
+    let home = env::var("HOME").or_else(|_| env::var("USERPROFILE")).ok()?;
+    Some(PathBuf::from(home).join(".adventofcode.session"))
+}
+
+/// Concatenates the `<article>` elements of a page. Returns the whole page if it has none.
+fn extract_articles(html: &str) -> String {
+    let mut articles = vec![];
+    let mut rest = html;
+
+    while let Some(start) = rest.find("<article") {
+        let Some(len) = rest[start..].find("</article>") else {
+            break;
+        };
+        let end = start + len + "</article>".len();
+        articles.push(&rest[start..end]);
+        rest = &rest[end..];
+    }
+
+    if articles.is_empty() {
+        html.to_string()
+    } else {
+        articles.join("\n")
+    }
+}
+
+/// Reduces html to its text content, e.g. to show the response to a submission.
+pub fn strip_tags(html: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+        .trim()
+        .to_string()
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        thread,
+    };
+
+    use super::{extract_articles, strip_tags, AocClient, AocClientError};
+    use crate::template::puzzle::{title, to_markdown};
+    use crate::{day, year};
+
+    /// Serves a single request with `status` and `body`, returns the base url and a handle that yields the raw request.
+    fn serve_once(status: u16, body: &'static str) -> (String, thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+        let handle = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut request = String::new();
+            let mut content_length = 0;
+
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some(len) = line.to_lowercase().strip_prefix("content-length:") {
+                    content_length = len.trim().parse().unwrap();
+                }
+                request.push_str(&line);
+                if line == "\r\n" {
+                    break;
+                }
+            }
+
+            let mut body_buf = vec![0; content_length];
+            reader.read_exact(&mut body_buf).unwrap();
+            request.push_str(&String::from_utf8(body_buf).unwrap());
+
+            let response = format!(
+                "HTTP/1.1 {status} OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            reader.get_mut().write_all(response.as_bytes()).unwrap();
+            request
+        });
+
+        (base_url, handle)
+    }
+
+    #[test]
+    fn test_get_input() {
+        let (base_url, handle) = serve_once(200, "1 2 3\n");
+        let client = AocClient::new(&base_url, "secret\n", year!(2023));
+        assert_eq!(client.get_input(day!(5)).unwrap(), "1 2 3\n");
+
+        let request = handle.join().unwrap();
+        assert!(request.starts_with("GET /2023/day/5/input HTTP/1.1"));
+        assert!(request.contains("Cookie: session=secret\r\n"));
+    }
+
+    #[test]
+    fn test_get_puzzle() {
+        let (base_url, handle) = serve_once(
+            200,
+            "<main><article><h2>Day 1</h2></article><p>x</p><article><p>Part 2</p></article></main>",
+        );
+        let client = AocClient::new(&base_url, "secret", year!(2023));
+        assert_eq!(
+            client.get_puzzle(day!(1)).unwrap(),
+            "<article><h2>Day 1</h2></article>\n<article><p>Part 2</p></article>"
+        );
+        assert!(handle
+            .join()
+            .unwrap()
+            .starts_with("GET /2023/day/1 HTTP/1.1"));
+    }
+
+    #[test]
+    fn test_submit() {
+        let (base_url, handle) =
+            serve_once(200, "<article><p>That's the right answer!</p></article>");
+        let client = AocClient::new(&base_url, "secret", year!(2023));
+        assert_eq!(
+            client.submit(day!(12), 2, "42").unwrap(),
+            "<article><p>That's the right answer!</p></article>"
+        );
+
+        let request = handle.join().unwrap();
+        assert!(request.starts_with("POST /2023/day/12/answer HTTP/1.1"));
+        assert!(request.ends_with("level=2&answer=42"));
+    }
+
+    #[test]
+    fn test_http_error() {
+        let (base_url, handle) = serve_once(404, "Not Found");
+        let client = AocClient::new(&base_url, "secret", year!(2023));
+        assert!(matches!(
+            client.get_input(day!(25)),
+            Err(AocClientError::Http(404))
+        ));
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_saved_page() {
+        let markdown = to_markdown(&extract_articles(include_str!("fixtures/page.html")));
+        assert_eq!(markdown, include_str!("fixtures/page.md"));
+        assert_eq!(title(&markdown).as_deref(), Some("Café Queue"));
+    }
+
+    #[test]
+    fn test_extract_articles_without_article() {
+        assert_eq!(extract_articles("<p>hi</p>"), "<p>hi</p>");
+    }
+
+    #[test]
+    fn test_strip_tags() {
+        assert_eq!(
+            strip_tags(
+                "<article><p>That's the <em>right</em> answer! &lt;3 &amp; more</p></article>"
+            ),
+            "That's the right answer! <3 & more"
+        );
+    }
+}
@@ -0,0 +1,319 @@
+//! Module that persists benchmark timings and compares new runs against them.
+//! Timings are stored in `data/YYYY/benchmarks.json`, one baseline per git commit.
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::Path,
+    process::Command,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use tinyjson::JsonValue;
+
+use crate::template::data_dir;
+use crate::Day;
+
+/// Path of the stored baselines of the current year.
+pub fn baseline_path() -> String {
+    data_dir("benchmarks.json").display().to_string()
+}
+
+/// Part number under which the parse phase of a two-phase solution is stored.
+pub const PARSE_PART: u8 = 0;
+
+#[derive(Debug)]
+pub enum Error {
+    Parser(String),
+    IO(io::Error),
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::IO(e)
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Parser(e) => write!(f, "could not parse benchmarks: {e}"),
+            Error::IO(e) => write!(f, "could not access benchmarks: {e}"),
+        }
+    }
+}
+
+/// Benchmarked time of one part of a day.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PartTiming {
+    pub day: Day,
+    pub part: u8,
+    pub duration: Duration,
+}
+
+/// Timings of all parts, recorded at a git commit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Baseline {
+    pub commit: String,
+    /// Seconds since the unix epoch.
+    pub timestamp: u64,
+    pub timings: Vec<PartTiming>,
+}
+
+impl Baseline {
+    /// Creates a baseline for the current `HEAD` commit.
+    pub fn new(timings: Vec<PartTiming>) -> Self {
+        Self {
+            commit: current_commit().unwrap_or_else(|| "unknown".into()),
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+            timings,
+        }
+    }
+
+    fn get(&self, day: Day, part: u8) -> Option<&PartTiming> {
+        self.timings.iter().find(|t| t.day == day && t.part == part)
+    }
+}
+
+/// All recorded baselines, oldest first.
+#[derive(Debug, Default, PartialEq)]
+pub struct Store {
+    pub baselines: Vec<Baseline>,
+}
+
+impl Store {
+    /// Reads the store from `path`. A missing file yields an empty store.
+    pub fn load(path: &str) -> Result<Self, Error> {
+        if !Path::new(path).exists() {
+            return Ok(Self::default());
+        }
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), Error> {
+        fs::write(path, self.to_json() + "\n")?;
+        Ok(())
+    }
+
+    /// Adds a baseline, replacing an existing one for the same commit.
+    pub fn insert(&mut self, baseline: Baseline) {
+        self.baselines.retain(|b| b.commit != baseline.commit);
+        self.baselines.push(baseline);
+    }
+
+    /// Finds the most recent baseline whose commit starts with `commit`, or the most recent one if `commit` is [`None`].
+    pub fn find(&self, commit: Option<&str>) -> Option<&Baseline> {
+        self.baselines
+            .iter()
+            .rev()
+            .find(|b| commit.is_none_or(|c| b.commit.starts_with(c)))
+    }
+
+    fn parse(s: &str) -> Result<Self, Error> {
+        let json: JsonValue = s.parse().map_err(|e| Error::Parser(format!("{e}")))?;
+
+        let baselines = field(&json, "baselines")
+            .and_then(|b| b.get::<Vec<JsonValue>>())
+            .ok_or_else(|| Error::Parser("expected a list of baselines.".into()))?
+            .iter()
+            .map(parse_baseline)
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| Error::Parser("malformed baseline.".into()))?;
+
+        Ok(Self { baselines })
+    }
+
+    /// Serializes the store with a stable key order, one timing per line, to keep diffs of the file readable.
+    fn to_json(&self) -> String {
+        let baselines: Vec<String> = self
+            .baselines
+            .iter()
+            .map(|b| {
+                let timings: Vec<String> = b
+                    .timings
+                    .iter()
+                    .map(|t| {
+                        format!(
+                            "        {{ \"day\": {}, \"part\": {}, \"nanos\": {} }}",
+                            t.day.into_inner(),
+                            t.part,
+                            t.duration.as_nanos()
+                        )
+                    })
+                    .collect();
+
+                // a string can always be stringified.
+                let commit = JsonValue::String(b.commit.clone()).stringify().unwrap();
+
+                format!(
+                    "    {{\n      \"commit\": {commit},\n      \"timestamp\": {},\n      \"timings\": [\n{}\n      ]\n    }}",
+                    b.timestamp,
+                    timings.join(",\n")
+                )
+            })
+            .collect();
+
+        format!("{{\n  \"baselines\": [\n{}\n  ]\n}}", baselines.join(",\n"))
+    }
+}
+
+fn field<'a>(json: &'a JsonValue, key: &str) -> Option<&'a JsonValue> {
+    json.get::<HashMap<String, JsonValue>>()?.get(key)
+}
+
+fn number(json: &JsonValue, key: &str) -> Option<f64> {
+    field(json, key)?.get::<f64>().copied()
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn parse_baseline(json: &JsonValue) -> Option<Baseline> {
+    let timings = field(json, "timings")?
+        .get::<Vec<JsonValue>>()?
+        .iter()
+        .map(|t| {
+            Some(PartTiming {
+                day: Day::new(number(t, "day")? as u8)?,
+                part: number(t, "part")? as u8,
+                duration: Duration::from_nanos(number(t, "nanos")? as u64),
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(Baseline {
+        commit: field(json, "commit")?.get::<String>()?.clone(),
+        timestamp: number(json, "timestamp")? as u64,
+        timings,
+    })
+}
+
+fn current_commit() -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Options for comparing a run against a stored baseline.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompareOptions {
+    /// Commit (or prefix) of the baseline to compare against, the most recent baseline if [`None`].
+    pub commit: Option<String>,
+    /// Slowdown in percent above which a part counts as regressed.
+    pub threshold: f64,
+}
+
+/// Change in timing of one part compared to a baseline.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Change {
+    pub day: Day,
+    pub part: u8,
+    pub before: Duration,
+    pub after: Duration,
+}
+
+impl Change {
+    /// Relative change in percent, positive values are slowdowns.
+    pub fn percent(&self) -> f64 {
+        (self.after.as_secs_f64() / self.before.as_secs_f64().max(f64::EPSILON) - 1.0) * 100.0
+    }
+
+    /// Whether the part got slower by more than `threshold` percent.
+    pub fn is_regression(&self, threshold: f64) -> bool {
+        self.percent() > threshold
+    }
+}
+
+/// Pairs every timing in `current` with its counterpart in `baseline`. Parts that are not part of the baseline are skipped.
+pub fn compare(baseline: &Baseline, current: &[PartTiming]) -> Vec<Change> {
+    current
+        .iter()
+        .filter_map(|t| {
+            let before = baseline.get(t.day, t.part)?;
+            Some(Change {
+                day: t.day,
+                part: t.part,
+                before: before.duration,
+                after: t.duration,
+            })
+        })
+        .collect()
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::time::Duration;
+
+    use super::{compare, Baseline, PartTiming, Store};
+    use crate::day;
+
+    fn get_mock_baseline(commit: &str, micros: u64) -> Baseline {
+        Baseline {
+            commit: commit.into(),
+            timestamp: 1_700_000_000,
+            timings: vec![
+                PartTiming {
+                    day: day!(1),
+                    part: 1,
+                    duration: Duration::from_micros(micros),
+                },
+                PartTiming {
+                    day: day!(1),
+                    part: 2,
+                    duration: Duration::from_micros(2 * micros),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let mut store = Store::default();
+        store.insert(get_mock_baseline("abc123", 10));
+        store.insert(get_mock_baseline("def456", 20));
+        assert_eq!(Store::parse(&store.to_json()).unwrap(), store);
+    }
+
+    #[test]
+    fn test_insert_replaces_commit() {
+        let mut store = Store::default();
+        store.insert(get_mock_baseline("abc123", 10));
+        store.insert(get_mock_baseline("def456", 20));
+        store.insert(get_mock_baseline("abc123", 30));
+        assert_eq!(store.baselines.len(), 2);
+        assert_eq!(store.find(None).unwrap().commit, "abc123");
+        assert_eq!(
+            store.find(Some("def")).unwrap().timings[0].duration,
+            Duration::from_micros(20)
+        );
+        assert_eq!(store.find(Some("xyz")), None);
+    }
+
+    #[test]
+    fn test_compare() {
+        let baseline = get_mock_baseline("abc123", 10);
+        let current = get_mock_baseline("def456", 12).timings;
+        let changes = compare(&baseline, &current);
+        assert_eq!(changes.len(), 2);
+        assert!((changes[0].percent() - 20.0).abs() < 1e-9);
+        assert!(changes[0].is_regression(10.0));
+        assert!(!changes[0].is_regression(25.0));
+    }
+
+    #[test]
+    fn test_compare_skips_new_parts() {
+        let baseline = get_mock_baseline("abc123", 10);
+        let current = [PartTiming {
+            day: day!(2),
+            part: 1,
+            duration: Duration::from_micros(10),
+        }];
+        assert!(compare(&baseline, &current).is_empty());
+    }
+}
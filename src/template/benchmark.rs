@@ -0,0 +1,190 @@
+//! Statistics for benchmarked solution runs.
+//! Samples are filtered for outliers with the median absolute deviation (MAD) before any statistic is computed.
+use std::time::Duration;
+
+/// Samples whose modified z-score exceeds this value are rejected as outliers.
+/// See: Iglewicz and Hoaglin, "How to Detect and Handle Outliers" (1993).
+const OUTLIER_THRESHOLD: f64 = 3.5;
+
+/// Scales the MAD to be a consistent estimator of the standard deviation for normally distributed samples.
+const MAD_SCALE: f64 = 1.4826;
+
+/// Controls how long a solution part is benched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BenchConfig {
+    /// Time spent running the solution before measuring, to warm up caches and branch predictors.
+    pub warmup_time: Duration,
+    /// Approximate time spent measuring, used to derive the number of iterations.
+    pub target_time: Duration,
+    /// A fixed number of iterations, takes precedence over `target_time`.
+    pub iterations: Option<u128>,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        Self {
+            warmup_time: Duration::from_millis(100),
+            target_time: Duration::from_secs(1),
+            iterations: None,
+        }
+    }
+}
+
+impl BenchConfig {
+    /// Number of measured iterations for a solution that takes `estimate` per run.
+    /// Unless a fixed count is configured, this is between 10 and 10.000 iterations.
+    pub fn iterations_for(&self, estimate: Duration) -> u128 {
+        self.iterations.unwrap_or_else(|| {
+            (self.target_time.as_nanos() / estimate.as_nanos().max(10)).clamp(10, 10000)
+        })
+    }
+}
+
+/// Summary statistics of a benchmark.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BenchStats {
+    pub median: Duration,
+    pub mean: Duration,
+    pub min: Duration,
+    pub max: Duration,
+    pub p95: Duration,
+    pub stddev: Duration,
+    /// Number of samples the statistics are computed from, after outlier rejection.
+    pub samples: u128,
+    /// Number of samples rejected as outliers.
+    pub outliers: u128,
+}
+
+impl BenchStats {
+    /// Computes statistics over `samples`, returns [`None`] if there are no samples.
+    pub fn from_samples(samples: &[Duration]) -> Option<Self> {
+        #[allow(clippy::cast_precision_loss)]
+        let mut nanos: Vec<f64> = samples.iter().map(|x| x.as_nanos() as f64).collect();
+        nanos.sort_by(f64::total_cmp);
+
+        let center = median(&nanos)?;
+
+        let mut deviations: Vec<f64> = nanos.iter().map(|x| (x - center).abs()).collect();
+        deviations.sort_by(f64::total_cmp);
+        let mad = median(&deviations)? * MAD_SCALE;
+
+        // if more than half of the samples are identical, the MAD is zero and no sample can be judged an outlier.
+        let kept: Vec<f64> = if mad > 0.0 {
+            nanos
+                .iter()
+                .copied()
+                .filter(|x| (x - center).abs() / mad <= OUTLIER_THRESHOLD)
+                .collect()
+        } else {
+            nanos.clone()
+        };
+
+        #[allow(clippy::cast_precision_loss)]
+        let count = kept.len() as f64;
+        let mean = kept.iter().sum::<f64>() / count;
+        let variance = if kept.len() > 1 {
+            kept.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (count - 1.0)
+        } else {
+            0.0
+        };
+
+        Some(Self {
+            median: from_nanos(median(&kept)?),
+            mean: from_nanos(mean),
+            min: from_nanos(*kept.first()?),
+            max: from_nanos(*kept.last()?),
+            p95: from_nanos(percentile(&kept, 0.95)?),
+            stddev: from_nanos(variance.sqrt()),
+            samples: kept.len() as u128,
+            outliers: (nanos.len() - kept.len()) as u128,
+        })
+    }
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn from_nanos(nanos: f64) -> Duration {
+    Duration::from_nanos(nanos.round() as u64)
+}
+
+/// Median of sorted values.
+fn median(sorted: &[f64]) -> Option<f64> {
+    let mid = sorted.len() / 2;
+    if sorted.is_empty() {
+        None
+    } else if sorted.len().is_multiple_of(2) {
+        Some((sorted[mid - 1] + sorted[mid]) / 2.0)
+    } else {
+        Some(sorted[mid])
+    }
+}
+
+/// Nearest-rank percentile of sorted values.
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss
+)]
+fn percentile(sorted: &[f64], p: f64) -> Option<f64> {
+    let rank = (p * sorted.len() as f64).ceil() as usize;
+    sorted.get(rank.saturating_sub(1)).copied()
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::time::Duration;
+
+    use super::{BenchConfig, BenchStats};
+
+    fn micros(values: &[u64]) -> Vec<Duration> {
+        values.iter().map(|&x| Duration::from_micros(x)).collect()
+    }
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(BenchStats::from_samples(&[]), None);
+    }
+
+    #[test]
+    fn test_stats() {
+        let stats = BenchStats::from_samples(&micros(&[4, 1, 3, 2, 5])).unwrap();
+        assert_eq!(stats.median, Duration::from_micros(3));
+        assert_eq!(stats.mean, Duration::from_micros(3));
+        assert_eq!(stats.min, Duration::from_micros(1));
+        assert_eq!(stats.max, Duration::from_micros(5));
+        assert_eq!(stats.p95, Duration::from_micros(5));
+        assert_eq!(stats.samples, 5);
+        assert_eq!(stats.outliers, 0);
+        // sample standard deviation of 1..=5 is sqrt(2.5).
+        assert_eq!(stats.stddev, Duration::from_nanos(1581));
+    }
+
+    #[test]
+    fn test_rejects_outliers() {
+        let stats = BenchStats::from_samples(&micros(&[10, 11, 9, 10, 12, 10, 500])).unwrap();
+        assert_eq!(stats.outliers, 1);
+        assert_eq!(stats.samples, 6);
+        assert_eq!(stats.max, Duration::from_micros(12));
+        assert_eq!(stats.median, Duration::from_micros(10));
+    }
+
+    #[test]
+    fn test_identical_samples() {
+        let stats = BenchStats::from_samples(&micros(&[7, 7, 7, 7, 9])).unwrap();
+        assert_eq!(stats.outliers, 0);
+        assert_eq!(stats.max, Duration::from_micros(9));
+    }
+
+    #[test]
+    fn test_iterations() {
+        let config = BenchConfig::default();
+        assert_eq!(config.iterations_for(Duration::from_millis(10)), 100);
+        assert_eq!(config.iterations_for(Duration::from_secs(5)), 10);
+        assert_eq!(config.iterations_for(Duration::ZERO), 10000);
+
+        let config = BenchConfig {
+            iterations: Some(3),
+            ..BenchConfig::default()
+        };
+        assert_eq!(config.iterations_for(Duration::from_millis(10)), 3);
+    }
+}
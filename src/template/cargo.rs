@@ -0,0 +1,72 @@
+//! Module that runs `cargo` for a day, e.g. to build it or run its tests, and reads the output of `cargo test`.
+use std::{
+    io,
+    process::{Command, Output},
+};
+
+/// Runs `cargo` with `args`, passing `bin_args` on to the binary, and captures its output.
+pub fn run(args: &[&str], release: bool, bin_args: &[&str]) -> io::Result<Output> {
+    let mut cmd = Command::new("cargo");
+    cmd.args(args);
+
+    if release {
+        cmd.arg("--release");
+    }
+
+    if !bin_args.is_empty() {
+        cmd.arg("--").args(bin_args);
+    }
+
+    cmd.output()
+}
+
+/// The outcome of `cargo test`.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct TestSummary {
+    pub passed: usize,
+    pub failed: Vec<String>,
+}
+
+impl TestSummary {
+    /// Reads the test lines of `cargo test`, [`None`] if no tests ran, e.g. because the build failed.
+    pub fn parse(stdout: &str) -> Option<Self> {
+        let mut summary = Self::default();
+        let mut has_result = false;
+
+        for line in stdout.lines() {
+            if line.starts_with("test result:") {
+                has_result = true;
+            }
+
+            let Some(test) = line.strip_prefix("test ") else {
+                continue;
+            };
+
+            if test.ends_with(" ... ok") {
+                summary.passed += 1;
+            } else if let Some(name) = test.strip_suffix(" ... FAILED") {
+                summary.failed.push(name.into());
+            }
+        }
+
+        has_result.then_some(summary)
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::TestSummary;
+
+    #[test]
+    fn test_summary() {
+        let stdout = "running 3 tests\ntest examples::part_one_05_1 ... ok\ntest tests::test_part_two ... FAILED\ntest tests::test_part_one ... ok\n\ntest result: FAILED. 2 passed; 1 failed; 0 ignored";
+        assert_eq!(
+            TestSummary::parse(stdout),
+            Some(TestSummary {
+                passed: 2,
+                failed: vec!["tests::test_part_two".into()]
+            })
+        );
+        assert_eq!(TestSummary::parse(""), None);
+    }
+}
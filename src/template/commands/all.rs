@@ -1,254 +1,551 @@
-use std::io;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::time::Duration;
+use std::{io, process, thread};
 
 use crate::template::{
+    answers::{self, Verdict},
+    baseline::{self, baseline_path, Baseline, CompareOptions, PartTiming, Store},
+    limits,
     readme_benchmarks::{self, Timings},
-    ANSI_BOLD, ANSI_ITALIC, ANSI_RESET,
+    registry::Registry,
+    runner::{print_parse, print_part, DayResult, Failure, Format, PartResult, RunOptions},
+    try_read_file, year, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET,
 };
-use crate::{all_days, Day};
+use crate::{Day, Days, Year};
+
+/// Which days and parts to run.
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    pub days: Days,
+    /// Only run days with a part that has no recorded answer yet.
+    pub only_unsolved: bool,
+    /// Only show days with a part that failed or whose answer doesn't match the recorded one.
+    pub only_failing: bool,
+    /// Only run this part, [`None`] runs both parts.
+    pub part: Option<u8>,
+}
+
+impl Filter {
+    fn is_set(&self) -> bool {
+        self.days != Days::all() || self.only_unsolved || self.only_failing || self.part.is_some()
+    }
+
+    pub(crate) fn parts(&self) -> Vec<u8> {
+        self.part.map_or(vec![1, 2], |part| vec![part])
+    }
+
+    /// The selected days of `year`, without solved ones with `only_unsolved`.
+    pub(crate) fn days(&self, registry: Registry, year: Year) -> Vec<Day> {
+        self.days
+            .iter()
+            .filter(|&day| {
+                !self.only_unsolved || registry.get(year, day).is_some() && self.is_unsolved(day)
+            })
+            .collect()
+    }
+
+    fn is_unsolved(&self, day: Day) -> bool {
+        self.parts()
+            .into_iter()
+            .any(|part| answers::read(day, part).is_none())
+    }
+
+    pub(crate) fn is_failing(&self, result: &DayResult) -> bool {
+        result.parts.iter().any(has_failed)
+    }
+}
+
+/// Options of `all` that are handled by the command itself, not by the solutions' [`RunOptions`].
+#[derive(Debug, Clone, Default)]
+pub struct AllOptions {
+    pub is_release: bool,
+    /// Compare the benchmarks with a stored baseline instead of storing them.
+    pub compare: Option<CompareOptions>,
+    /// The number of days to run at the same time.
+    pub jobs: usize,
+    pub filter: Filter,
+}
+
+/// Whether the part failed to run or, when verified, gave the wrong answer.
+fn has_failed(part: &PartResult) -> bool {
+    part.error.is_some() || matches!(part.verdict, Some(Verdict::Fail { .. }))
+}
+
+/// What happened when running a single day.
+enum DayRun {
+    Unsolved,
+    Unreadable(io::Error),
+    Ran(Box<DayResult>),
+}
+
+/// Runs every scaffolded day in-process.
+/// [`AllOptions::is_release`] only controls whether the README and the stored baseline are updated, the optimization level is that of the running binary.
+/// With `compare`, the run is benched and compared against a stored baseline instead, exiting with an error if any part regressed.
+/// With [`Format::Json`], the JSON records of each part are written to the file set with [`set_output`](crate::template::runner::set_output) instead of being printed.
+/// A failing part doesn't stop the run, but makes it exit with an error at the end. With [`RunOptions::limits`], a part that
+/// takes too long or uses too much memory fails and its process is killed, see [`limits::run`].
+/// With more than one job, days run concurrently and their output is printed in day order once it's complete.
+/// Benchmarks always run one day at a time, so they don't compete for the CPU.
+/// Benchmarks of a [`Filter`]ed run are never stored, as they don't cover every day.
+pub fn handle(registry: Registry, options: RunOptions, all: AllOptions) {
+    let AllOptions {
+        is_release,
+        compare,
+        jobs,
+        filter,
+    } = all;
+    let is_timed = options.is_timed || compare.is_some();
+    let mut jobs = jobs.max(1);
+
+    if is_timed && jobs > 1 {
+        eprintln!("Benchmarks run one day at a time, ignoring --jobs.");
+        jobs = 1;
+    }
 
-pub fn handle(is_release: bool, is_timed: bool) {
+    let options = RunOptions {
+        is_timed,
+        verify: filter.only_failing,
+        // results are printed once the day completed, so days can be left out or put in order.
+        is_quiet: jobs > 1 || filter.only_failing,
+        part: filter.part,
+        ..options
+    };
+    let format = options.format;
+    let is_human = format == Format::Human;
     let mut timings: Vec<Timings> = vec![];
+    let mut part_timings: Vec<PartTiming> = vec![];
+    let year = year();
+    let mut failures = 0;
+
+    let days = filter.days(registry, year);
+
+    if days.is_empty() {
+        eprintln!("No days match the filters.");
+        return;
+    }
+
+    let mut is_first = true;
+    let mut print_header = |day: Day| {
+        if is_human {
+            if !is_first {
+                println!();
+            }
+            is_first = false;
+
+            println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
+            println!("------");
+        }
+    };
+
+    let run_day = |day: Day| {
+        let Some(solution) = registry.get(year, day) else {
+            return DayRun::Unsolved;
+        };
 
-    all_days().for_each(|day| {
-        if day > 1 {
-            println!();
+        match try_read_file("inputs", day) {
+            Ok(input) => DayRun::Ran(Box::new(limits::run(solution, &input, &options))),
+            Err(e) => DayRun::Unreadable(e),
         }
+    };
 
-        println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
-        println!("------");
+    let mut report = |day: Day, run: DayRun| match run {
+        DayRun::Unsolved => {
+            if is_human {
+                println!("Not solved.");
+            }
+        }
+        DayRun::Unreadable(e) => eprintln!("Could not read input file: {e}"),
+        DayRun::Ran(result) => {
+            failures += result.parts.iter().filter(|p| has_failed(p)).count();
 
-        let output = child_commands::run_solution(day, is_timed, is_release).unwrap();
+            if is_timed {
+                timings.push(collect_timings(day, &result));
+                part_timings.extend(collect_part_timings(day, &result));
+            }
+        }
+    };
+
+    if options.is_quiet {
+        run_parallel(&days, jobs, run_day, |day, run| {
+            let is_shown = match &run {
+                DayRun::Ran(result) => !filter.only_failing || filter.is_failing(result),
+                DayRun::Unsolved => !filter.only_failing,
+                DayRun::Unreadable(_) => true,
+            };
+
+            if is_shown {
+                print_header(day);
+                if let DayRun::Ran(result) = &run {
+                    print_day(result, &filter, format);
+                }
+            }
 
-        if output.is_empty() {
-            println!("Not solved.");
-        } else {
-            let val = child_commands::parse_exec_time(&output, day);
-            timings.push(val);
+            report(day, run);
+        });
+    } else {
+        for day in days {
+            print_header(day);
+            report(day, run_day(day));
         }
-    });
+    }
 
     if is_timed {
         let total_millis = timings.iter().map(|x| x.total_nanos).sum::<f64>() / 1_000_000_f64;
 
-        println!("\n{ANSI_BOLD}Total:{ANSI_RESET} {ANSI_ITALIC}{total_millis:.2}ms{ANSI_RESET}");
+        if is_human {
+            println!(
+                "\n{ANSI_BOLD}Total:{ANSI_RESET} {ANSI_ITALIC}{total_millis:.2}ms{ANSI_RESET}"
+            );
+        }
 
-        if is_release {
+        if let Some(compare) = compare {
+            compare_with_baseline(&part_timings, &compare);
+        } else if is_release && filter.is_set() {
+            eprintln!("Not storing benchmarks, as only some days or parts ran.");
+        } else if is_release {
             match readme_benchmarks::update(timings, total_millis) {
-                Ok(()) => println!("Successfully updated README with benchmarks."),
+                Ok(()) => eprintln!("Successfully updated README with benchmarks."),
                 Err(_) => {
                     eprintln!("Failed to update readme with benchmarks.");
                 }
             }
+
+            match save_baseline(part_timings) {
+                Ok(commit) => {
+                    eprintln!(
+                        "Stored benchmarks for commit {commit} in {}.",
+                        baseline_path()
+                    )
+                }
+                Err(e) => eprintln!("Failed to store benchmarks: {e}"),
+            }
         }
     }
-}
-
-#[derive(Debug)]
-pub enum Error {
-    BrokenPipe,
-    Parser(String),
-    IO(io::Error),
-}
 
-impl From<std::io::Error> for Error {
-    fn from(e: std::io::Error) -> Self {
-        Error::IO(e)
+    if failures > 0 {
+        eprintln!("\n{failures} part(s) failed.");
+        process::exit(1);
     }
 }
 
-#[must_use]
-pub fn get_path_for_bin(day: Day) -> String {
-    format!("./src/bin/{day}.rs")
+fn save_baseline(part_timings: Vec<PartTiming>) -> Result<String, baseline::Error> {
+    let path = baseline_path();
+    let mut store = Store::load(&path)?;
+    let baseline = Baseline::new(part_timings);
+    let commit = baseline.commit.clone();
+    store.insert(baseline);
+    store.save(&path)?;
+    Ok(commit)
 }
 
-/// All solutions live in isolated binaries.
-/// This module encapsulates interaction with these binaries, both invoking them as well as parsing the timing output.
-mod child_commands {
-    use super::{get_path_for_bin, Error};
-    use crate::Day;
-    use std::{
-        io::{BufRead, BufReader},
-        path::Path,
-        process::{Command, Stdio},
-        thread,
+/// Prints the change of every part against the stored baseline. Exits with an error if a part regressed.
+/// Comparison output goes to stderr, so it doesn't interfere with `--format json`.
+fn compare_with_baseline(part_timings: &[PartTiming], options: &CompareOptions) {
+    let path = baseline_path();
+    let store = match Store::load(&path) {
+        Ok(store) => store,
+        Err(e) => {
+            eprintln!("Failed to load benchmarks: {e}");
+            process::exit(1);
+        }
     };
 
-    /// Run the solution bin for a given day
-    pub fn run_solution(day: Day, is_timed: bool, is_release: bool) -> Result<Vec<String>, Error> {
-        // skip command invocation for days that have not been scaffolded yet.
-        if !Path::new(&get_path_for_bin(day)).exists() {
-            return Ok(vec![]);
-        }
+    let Some(baseline) = store.find(options.commit.as_deref()) else {
+        eprintln!("No stored benchmarks found in {path}. Run `cargo time` to create a baseline.");
+        process::exit(1);
+    };
 
-        let day_padded = day.to_string();
-        let mut args = vec!["run", "--quiet", "--bin", &day_padded];
+    eprintln!(
+        "\n{ANSI_BOLD}Compared to {}{ANSI_RESET} (threshold: {}%)",
+        baseline.commit, options.threshold
+    );
 
-        if is_release {
-            args.push("--release");
-        }
+    let changes = baseline::compare(baseline, part_timings);
+    let mut regressions = 0;
 
-        if is_timed {
-            // mirror `--time` flag to child invocations.
-            args.push("--");
-            args.push("--time");
+    for change in &changes {
+        let is_regression = change.is_regression(options.threshold);
+        if is_regression {
+            regressions += 1;
         }
 
-        // spawn child command with piped stdout/stderr.
-        // forward output to stdout/stderr while grabbing stdout lines.
-
-        let mut cmd = Command::new("cargo")
-            .args(&args)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()?;
+        let phase = match change.part {
+            baseline::PARSE_PART => "Parse".to_string(),
+            part => format!("Part {part}"),
+        };
 
-        let stdout = BufReader::new(cmd.stdout.take().ok_or(super::Error::BrokenPipe)?);
-        let stderr = BufReader::new(cmd.stderr.take().ok_or(super::Error::BrokenPipe)?);
+        eprintln!(
+            "Day {}, {phase}: {:.1?} -> {:.1?} ({:+.1}%){}",
+            change.day,
+            change.before,
+            change.after,
+            change.percent(),
+            if is_regression {
+                format!(" {ANSI_BOLD}✖ regression{ANSI_RESET}")
+            } else {
+                String::new()
+            }
+        );
+    }
 
-        let mut output = vec![];
+    if regressions > 0 {
+        eprintln!(
+            "\n{regressions} part(s) regressed by more than {}%.",
+            options.threshold
+        );
+        process::exit(1);
+    }
+}
 
-        let thread = thread::spawn(move || {
-            stderr.lines().for_each(|line| {
-                eprintln!("{}", line.unwrap());
+/// Runs `run` for every day on `jobs` threads. Results are handed to `report` in day order,
+/// each as soon as it and all the days before it completed.
+fn run_parallel<T: Send>(
+    days: &[Day],
+    jobs: usize,
+    run: impl Fn(Day) -> T + Sync,
+    mut report: impl FnMut(Day, T),
+) {
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.min(days.len()) {
+            let sender = sender.clone();
+            let (next, run) = (&next, &run);
+
+            scope.spawn(move || {
+                while let Some(&day) = days.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    if sender.send((day, run(day))).is_err() {
+                        break;
+                    }
+                }
             });
-        });
-
-        for line in stdout.lines() {
-            let line = line.unwrap();
-            println!("{line}");
-            output.push(line);
         }
 
-        thread.join().unwrap();
-        cmd.wait()?;
+        drop(sender);
 
-        Ok(output)
-    }
+        let mut completed = BTreeMap::new();
+        let mut pending = days.iter().copied().peekable();
 
-    pub fn parse_exec_time(output: &[String], day: Day) -> super::Timings {
-        let mut timings = super::Timings {
-            day,
-            part_1: None,
-            part_2: None,
-            total_nanos: 0_f64,
-        };
+        for (day, result) in receiver {
+            completed.insert(day, result);
 
-        output
-            .iter()
-            .filter_map(|l| {
-                if !l.contains(" samples)") {
-                    return None;
-                }
+            while let Some(result) = pending.peek().and_then(|day| completed.remove(day)) {
+                report(pending.next().unwrap(), result);
+            }
+        }
+    });
+}
 
-                let Some((timing_str, nanos)) = parse_time(l) else {
-                    eprintln!("Could not parse timings from line: {l}");
-                    return None;
-                };
+/// Prints the results of a day that ran quietly, as they would have been printed while running.
+pub(crate) fn print_day(result: &DayResult, filter: &Filter, format: Format) {
+    if let Some(parse) = &result.parse {
+        print_parse(parse, format);
+    }
 
-                let part = l.split(':').next()?;
-                Some((part, timing_str, nanos))
-            })
-            .for_each(|(part, timing_str, nanos)| {
-                if part.contains("Part 1") {
-                    timings.part_1 = Some(timing_str.into());
-                } else if part.contains("Part 2") {
-                    timings.part_2 = Some(timing_str.into());
-                }
+    for part in result
+        .parts
+        .iter()
+        .filter(|p| filter.parts().contains(&p.part))
+    {
+        print_part(part, format);
+    }
+}
 
-                timings.total_nanos += nanos;
-            });
+/// Only parts that produced an answer contribute to the timings. Phases that timed out are marked with the timeout.
+fn collect_timings(day: Day, result: &DayResult) -> Timings {
+    let mut timings = Timings {
+        day,
+        parse: None,
+        part_1: None,
+        part_2: None,
+        total_nanos: 0_f64,
+    };
 
-        timings
+    if let Some(parse) = &result.parse {
+        timings.parse = Some(match &parse.error {
+            Some(Failure::TimedOut(limit)) => timed_out(*limit),
+            _ => format!("{:.1?}", parse.duration),
+        });
+        timings.total_nanos += as_nanos(parse.duration);
     }
 
-    fn parse_to_float(s: &str, postfix: &str) -> Option<f64> {
-        s.split(postfix).next()?.parse().ok()
+    for part in &result.parts {
+        let timing_str = match (&part.answer, &part.error) {
+            (Some(_), _) => format!("{:.1?}", part.duration),
+            (None, Some(Failure::TimedOut(limit))) => {
+                match part.part {
+                    1 => timings.part_1 = Some(timed_out(*limit)),
+                    _ => timings.part_2 = Some(timed_out(*limit)),
+                }
+                continue;
+            }
+            (None, _) => continue,
+        };
+
+        match part.part {
+            1 => timings.part_1 = Some(timing_str),
+            _ => timings.part_2 = Some(timing_str),
+        }
+
+        timings.total_nanos += as_nanos(part.duration);
     }
 
-    fn parse_time(line: &str) -> Option<(&str, f64)> {
-        // for possible time formats, see: https://github.com/rust-lang/rust/blob/1.64.0/library/core/src/time.rs#L1176-L1200
-        let str_timing = line
-            .split(" samples)")
-            .next()?
-            .split('(')
-            .last()?
-            .split('@')
-            .next()?
-            .trim();
+    timings
+}
 
-        let parsed_timing = match str_timing {
-            s if s.contains("ns") => s.split("ns").next()?.parse::<f64>().ok(),
-            s if s.contains("µs") => parse_to_float(s, "µs").map(|x| x * 1000_f64),
-            s if s.contains("ms") => parse_to_float(s, "ms").map(|x| x * 1_000_000_f64),
-            s => parse_to_float(s, "s").map(|x| x * 1_000_000_000_f64),
-        }?;
+fn timed_out(limit: Duration) -> String {
+    format!("⏱ >{limit:.1?}")
+}
 
-        Some((str_timing, parsed_timing))
-    }
+/// Timings to store in the baseline, parsing is stored as [`baseline::PARSE_PART`].
+fn collect_part_timings(day: Day, result: &DayResult) -> Vec<PartTiming> {
+    let parse = result
+        .parse
+        .iter()
+        .filter(|parse| parse.error.is_none())
+        .map(|parse| PartTiming {
+            day,
+            part: baseline::PARSE_PART,
+            duration: parse.duration,
+        });
 
-    /// copied from: https://github.com/rust-lang/rust/blob/1.64.0/library/std/src/macros.rs#L328-L333
-    #[cfg(feature = "test_lib")]
-    macro_rules! assert_approx_eq {
-        ($a:expr, $b:expr) => {{
-            let (a, b) = (&$a, &$b);
-            assert!(
-                (*a - *b).abs() < 1.0e-6,
-                "{} is not approximately equal to {}",
-                *a,
-                *b
-            );
-        }};
-    }
+    let parts = result
+        .parts
+        .iter()
+        .filter(|r| r.answer.is_some())
+        .map(|r| PartTiming {
+            day,
+            part: r.part,
+            duration: r.duration,
+        });
 
-    #[cfg(feature = "test_lib")]
-    mod tests {
-        use super::parse_exec_time;
+    parse.chain(parts).collect()
+}
 
-        use crate::day;
+#[allow(clippy::cast_precision_loss)]
+fn as_nanos(duration: Duration) -> f64 {
+    duration.as_nanos() as f64
+}
 
-        #[test]
-        fn test_well_formed() {
-            let res = parse_exec_time(
-                &[
-                    "Part 1: 0 (74.13ns @ 100000 samples)".into(),
-                    "Part 2: 10 (74.13ms @ 99999 samples)".into(),
-                    "".into(),
-                ],
-                day!(1),
-            );
-            assert_approx_eq!(res.total_nanos, 74130074.13_f64);
-            assert_eq!(res.part_1.unwrap(), "74.13ns");
-            assert_eq!(res.part_2.unwrap(), "74.13ms");
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::time::Duration;
+
+    use super::{collect_part_timings, collect_timings, run_parallel};
+    use crate::day;
+    use crate::template::runner::{DayResult, Failure, ParseResult, PartResult};
+
+    fn part_result(part: u8, answer: Option<&str>, nanos: u64) -> PartResult {
+        PartResult {
+            day: day!(1),
+            part,
+            answer: answer.map(Into::into),
+            duration: Duration::from_nanos(nanos),
+            samples: 10,
+            stats: None,
+            verdict: None,
+            error: None,
         }
+    }
 
-        #[test]
-        fn test_patterns_in_input() {
-            let res = parse_exec_time(
-                &[
-                    "Part 1: @ @ @ ( ) ms (2s @ 5 samples)".into(),
-                    "Part 2: 10s (100ms @ 1 samples)".into(),
-                    "".into(),
-                ],
-                day!(1),
-            );
-            assert_approx_eq!(res.total_nanos, 2100000000_f64);
-            assert_eq!(res.part_1.unwrap(), "2s");
-            assert_eq!(res.part_2.unwrap(), "100ms");
+    fn parse_result(nanos: u64) -> ParseResult {
+        ParseResult {
+            day: day!(1),
+            duration: Duration::from_nanos(nanos),
+            samples: 10,
+            stats: None,
+            error: None,
         }
+    }
 
-        #[test]
-        fn test_missing_parts() {
-            let res = parse_exec_time(
-                &[
-                    "Part 1: ✖        ".into(),
-                    "Part 2: ✖        ".into(),
-                    "".into(),
+    #[test]
+    fn test_well_formed() {
+        let res = collect_timings(
+            day!(1),
+            &DayResult {
+                parse: None,
+                parts: [
+                    part_result(1, Some("0"), 74),
+                    part_result(2, Some("10"), 74_130_000),
                 ],
-                day!(1),
-            );
-            assert_approx_eq!(res.total_nanos, 0_f64);
-            assert_eq!(res.part_1.is_none(), true);
-            assert_eq!(res.part_2.is_none(), true);
-        }
+            },
+        );
+        assert_eq!(res.total_nanos, 74_130_074_f64);
+        assert_eq!(res.parse.is_none(), true);
+        assert_eq!(res.part_1.unwrap(), "74.0ns");
+        assert_eq!(res.part_2.unwrap(), "74.1ms");
+    }
+
+    #[test]
+    fn test_parse_phase() {
+        let result = DayResult {
+            parse: Some(parse_result(1_000)),
+            parts: [part_result(1, Some("0"), 74), part_result(2, None, 100)],
+        };
+
+        let res = collect_timings(day!(1), &result);
+        assert_eq!(res.total_nanos, 1_074_f64);
+        assert_eq!(res.parse.unwrap(), "1.0µs");
+
+        let part_timings = collect_part_timings(day!(1), &result);
+        assert_eq!(
+            part_timings.iter().map(|t| t.part).collect::<Vec<_>>(),
+            vec![0, 1]
+        );
+    }
+
+    #[test]
+    fn test_missing_parts() {
+        let res = collect_timings(
+            day!(1),
+            &DayResult {
+                parse: None,
+                parts: [part_result(1, None, 100), part_result(2, None, 100)],
+            },
+        );
+        assert_eq!(res.total_nanos, 0_f64);
+        assert_eq!(res.part_1.is_none(), true);
+        assert_eq!(res.part_2.is_none(), true);
+    }
+
+    #[test]
+    fn test_timed_out_part() {
+        let mut timed_out = part_result(2, None, 0);
+        timed_out.error = Some(Failure::TimedOut(Duration::from_secs(5)));
+
+        let res = collect_timings(
+            day!(1),
+            &DayResult {
+                parse: None,
+                parts: [part_result(1, Some("0"), 74), timed_out],
+            },
+        );
+        assert_eq!(res.total_nanos, 74_f64);
+        assert_eq!(res.part_2.unwrap(), "⏱ >5.0s");
+    }
+
+    #[test]
+    fn test_run_parallel_reports_in_order() {
+        let days: Vec<_> = (1..=6).map(|d| crate::Day::new(d).unwrap()).collect();
+        let mut reported = vec![];
+
+        run_parallel(
+            &days,
+            3,
+            |day| {
+                // later days finish first.
+                std::thread::sleep(Duration::from_millis(u64::from(7 - day.into_inner()) * 5));
+                day.into_inner() * 10
+            },
+            |day, result| reported.push((day.into_inner(), result)),
+        );
+
+        assert_eq!(
+            reported,
+            vec![(1, 10), (2, 20), (3, 30), (4, 40), (5, 50), (6, 60)]
+        );
     }
 }
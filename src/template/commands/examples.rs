@@ -0,0 +1,120 @@
+use std::{fs, io, process};
+
+use crate::template::aoc_client::get_puzzle_html_path;
+use crate::template::{data_dir, examples};
+use crate::Day;
+
+/// Extracts the examples from the downloaded puzzle description into `data/YYYY/examples`.
+/// Existing example files are only replaced if they are empty or `overwrite` is set.
+/// With `tests`, the expected answers are added to the example manifest `data/YYYY/examples/NN.toml`, which is turned into tests.
+pub fn handle(day: Day, overwrite: bool, tests: bool) {
+    let puzzle_path = get_puzzle_html_path(day);
+
+    let Ok(html) = fs::read_to_string(&puzzle_path) else {
+        eprintln!("Could not read \"{puzzle_path}\". Run `cargo download {day}` first.");
+        process::exit(1);
+    };
+
+    let examples = examples::extract(&html);
+
+    if examples.is_empty() {
+        eprintln!("No examples found in \"{puzzle_path}\".");
+        process::exit(1);
+    }
+
+    if let Err(e) = fs::create_dir_all(data_dir("examples")) {
+        eprintln!("Failed to create data directory: {e}");
+        process::exit(1);
+    }
+
+    for (i, example) in examples.iter().enumerate() {
+        let file_name = examples::file_name(day, i, examples.len());
+        let path = data_dir("examples").join(file_name);
+
+        let answers: Vec<String> = example
+            .answers
+            .iter()
+            .zip(1..)
+            .filter_map(|(answer, part)| Some(format!("part {part}: {}", answer.as_ref()?)))
+            .collect();
+
+        let is_empty = fs::read_to_string(&path).map_or(true, |s| s.trim().is_empty());
+
+        if !is_empty && !overwrite {
+            println!(
+                "Skipped \"{}\", it already exists. Pass --overwrite to replace it.",
+                path.display()
+            );
+            continue;
+        }
+
+        match fs::write(&path, &example.input) {
+            Ok(()) => println!(
+                "Wrote example to \"{}\" ({}).",
+                path.display(),
+                answers.join(", ")
+            ),
+            Err(e) => {
+                eprintln!("Failed to write example file: {e}");
+                process::exit(1);
+            }
+        }
+    }
+
+    // the empty file created by `scaffold` would be mistaken for an example.
+    let scaffolded = data_dir("examples").join(format!("{day}.txt"));
+    if examples.len() > 1
+        && fs::read_to_string(&scaffolded).is_ok_and(|s| s.is_empty())
+        && fs::remove_file(&scaffolded).is_ok()
+    {
+        println!("Removed empty example file \"{}\".", scaffolded.display());
+    }
+
+    if tests {
+        update_manifest(day, &examples);
+    }
+}
+
+fn update_manifest(day: Day, examples: &[examples::Example]) {
+    let manifest_path = data_dir("examples").join(format!("{day}.toml"));
+
+    let manifest = match fs::read_to_string(&manifest_path) {
+        Ok(manifest) => manifest,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(e) => {
+            eprintln!("Could not read \"{}\": {e}", manifest_path.display());
+            process::exit(1);
+        }
+    };
+
+    let (manifest, added) = match examples::update_manifest(&manifest, day, examples) {
+        Ok(updated) => updated,
+        Err(e) => {
+            eprintln!("Could not update \"{}\": {e}", manifest_path.display());
+            process::exit(1);
+        }
+    };
+
+    if added.is_empty() {
+        println!(
+            "\"{}\" already lists every example.",
+            manifest_path.display()
+        );
+        return;
+    }
+
+    match fs::write(&manifest_path, manifest) {
+        Ok(()) => {
+            for (file, part) in added {
+                println!(
+                    "Added a test for part {part} of \"{file}\" to \"{}\".",
+                    manifest_path.display()
+                );
+            }
+        }
+        Err(e) => {
+            eprintln!("Failed to write manifest: {e}");
+            process::exit(1);
+        }
+    }
+}
@@ -1,5 +1,10 @@
 pub mod all;
 pub mod download;
+pub mod examples;
 pub mod read;
+pub mod readme;
 pub mod scaffold;
 pub mod solve;
+pub mod status;
+pub mod verify;
+pub mod watch;
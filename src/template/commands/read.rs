@@ -1,16 +1,23 @@
 use std::process;
 
-use crate::template::aoc_cli;
+use crate::template::aoc_client::AocClient;
+use crate::template::puzzle;
 use crate::Day;
 
 pub fn handle(day: Day) {
-    if aoc_cli::check().is_err() {
-        eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
-        process::exit(1);
-    }
+    let client = match AocClient::from_env() {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("failed to set up client: {e}");
+            process::exit(1);
+        }
+    };
 
-    if let Err(e) = aoc_cli::read(day) {
-        eprintln!("failed to call aoc-cli: {e}");
-        process::exit(1);
+    match client.get_puzzle(day) {
+        Ok(html) => print!("{}", puzzle::to_terminal(&html)),
+        Err(e) => {
+            eprintln!("failed to read puzzle: {e}");
+            process::exit(1);
+        }
     };
 }
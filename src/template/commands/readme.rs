@@ -0,0 +1,73 @@
+use std::{fs, process};
+
+use crate::template::baseline::{baseline_path, Store};
+use crate::template::readme::{self, LinesOfCode, Puzzles, Section, Stars};
+use crate::template::readme_benchmarks::BenchmarkTable;
+
+const README_PATH: &str = "README.md";
+
+/// Regenerates every section of the readme that has markers. With `dry_run`, prints the changes instead of writing them.
+pub fn handle(dry_run: bool) {
+    let original = match fs::read_to_string(README_PATH) {
+        Ok(readme) => readme,
+        Err(e) => {
+            eprintln!("Failed to read {README_PATH}: {e}");
+            process::exit(1);
+        }
+    };
+
+    let benchmarks = match Store::load(&baseline_path()) {
+        Ok(store) => store.find(None).map(BenchmarkTable::from_baseline),
+        Err(e) => {
+            eprintln!("Could not read benchmarks, skipping the benchmark table: {e}");
+            None
+        }
+    };
+
+    let stars = Stars::collect();
+    let puzzles = Puzzles::collect();
+    let lines_of_code = LinesOfCode::collect();
+
+    let mut sections: Vec<&dyn Section> = vec![&stars, &puzzles, &lines_of_code];
+    if let Some(benchmarks) = &benchmarks {
+        sections.push(benchmarks);
+    }
+
+    let mut updated = original.clone();
+    let names = match readme::update_sections(&mut updated, &sections) {
+        Ok(names) => names,
+        Err(e) => {
+            eprintln!("Failed to update {README_PATH}: {e}");
+            process::exit(1);
+        }
+    };
+
+    if names.is_empty() {
+        let markers: Vec<String> = sections
+            .iter()
+            .map(|section| readme::marker(section.name()))
+            .collect();
+        eprintln!(
+            "{README_PATH} has no generated sections. Add a pair of one of these markers where a section should go, each on a line of its own: {}",
+            markers.join(", ")
+        );
+        process::exit(1);
+    }
+
+    if dry_run {
+        let diff = readme::diff(&original, &updated);
+        if diff.is_empty() {
+            println!("{README_PATH} is up to date.");
+        } else {
+            println!("{}", diff.join("\n"));
+        }
+        return;
+    }
+
+    if let Err(e) = fs::write(README_PATH, &updated) {
+        eprintln!("Failed to write {README_PATH}: {e}");
+        process::exit(1);
+    }
+
+    println!("Updated sections of {README_PATH}: {}.", names.join(", "));
+}
@@ -1,94 +1,492 @@
 use std::{
-    fs::{File, OpenOptions},
-    io::Write,
+    error::Error,
+    fmt::Display,
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
     process,
 };
 
-use crate::Day;
+use crate::template::aoc_client::{self, AocClient, AocClientError};
+use crate::template::{bin_name, data_dir, puzzle, year};
+use crate::{Day, Year};
 
-const MODULE_TEMPLATE: &str = r#"advent_of_code::solution!(DAY_NUMBER);
+/// Directory of project templates, e.g. `.aoc/templates/grid.rs` is selected with `--template grid`.
+/// A project template named like a built-in one replaces it.
+const TEMPLATE_DIR: &str = ".aoc/templates";
 
-pub fn part_one(input: &str) -> Option<u32> {
+/// The type of the answers of the built-in templates, unless `--answer-type` is passed.
+pub const DEFAULT_ANSWER_TYPE: &str = "u32";
+
+const MODULE_TEMPLATE: &str = r#"advent_of_code::solution!({{day}});
+
+pub fn part_one(input: &str) -> Option<{{answer_type}}> {
     None
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
+pub fn part_two(input: &str) -> Option<{{answer_type}}> {
     None
 }
+"#;
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Template for `--result`, whose parts report errors instead of panicking. Parts start out [`Unsolved`](crate::template::runner::Unsolved), like the `None` of the default template.
+const RESULT_MODULE_TEMPLATE: &str = r#"use std::error::Error;
 
-    #[test]
-    fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+use advent_of_code::template::runner::Unsolved;
+
+advent_of_code::solution!({{day}});
+
+pub fn part_one(input: &str) -> Result<{{answer_type}}, Box<dyn Error>> {
+    Err(Unsolved.into())
+}
+
+pub fn part_two(input: &str) -> Result<{{answer_type}}, Box<dyn Error>> {
+    Err(Unsolved.into())
+}
+"#;
+
+const BUILT_IN_TEMPLATES: [(&str, &str); 2] = [
+    ("default", MODULE_TEMPLATE),
+    ("result", RESULT_MODULE_TEMPLATE),
+];
+
+/// The values of the variables a template may use, e.g. `{{day}}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Variables {
+    day: Day,
+    year: Year,
+    /// The title of the puzzle, or `Day N` if its description was not downloaded yet.
+    title: String,
+    answer_type: String,
+}
+
+impl Variables {
+    fn get(&self, name: &str) -> Option<String> {
+        match name {
+            "day" => Some(self.day.into_inner().to_string()),
+            "year" => Some(self.year.to_string()),
+            "title" => Some(self.title.clone()),
+            "answer_type" => Some(self.answer_type.clone()),
+            _ => None,
+        }
     }
+}
 
-    #[test]
-    fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+#[derive(Debug)]
+enum TemplateError {
+    NotFound {
+        name: String,
+        available: Vec<String>,
+    },
+    UnknownVariable(String),
+    Io(PathBuf, io::Error),
+}
+
+impl Display for TemplateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TemplateError::NotFound { name, available } => write!(
+                f,
+                "there is no template \"{name}\", available templates are: {}",
+                available.join(", ")
+            ),
+            TemplateError::UnknownVariable(name) => write!(
+                f,
+                "unknown variable {{{{{name}}}}}, templates may use {{{{day}}}}, {{{{year}}}}, {{{{title}}}} and {{{{answer_type}}}}"
+            ),
+            TemplateError::Io(path, e) => write!(f, "could not read {}: {e}", path.display()),
+        }
     }
 }
-"#;
 
-fn safe_create_file(path: &str) -> Result<File, std::io::Error> {
-    OpenOptions::new().write(true).create_new(true).open(path)
+impl Error for TemplateError {}
+
+/// Reads the project template `name`, falling back to the built-in template of that name.
+fn load_template(dir: &Path, name: &str) -> Result<String, TemplateError> {
+    let path = dir.join(format!("{name}.rs"));
+
+    match fs::read_to_string(&path) {
+        Ok(template) => return Ok(template),
+        Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(TemplateError::Io(path, e)),
+        Err(_) => {}
+    }
+
+    BUILT_IN_TEMPLATES
+        .iter()
+        .find(|(built_in, _)| *built_in == name)
+        .map(|(_, template)| template.to_string())
+        .ok_or_else(|| TemplateError::NotFound {
+            name: name.to_string(),
+            available: available_templates(dir),
+        })
 }
 
-fn create_file(path: &str) -> Result<File, std::io::Error> {
-    OpenOptions::new().write(true).create(true).open(path)
+/// The names of the built-in and project templates.
+fn available_templates(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = BUILT_IN_TEMPLATES
+        .iter()
+        .map(|(name, _)| name.to_string())
+        .collect();
+
+    if let Ok(entries) = fs::read_dir(dir) {
+        names.extend(entries.filter_map(Result::ok).filter_map(|entry| {
+            let path = entry.path();
+            let is_template = path.extension().is_some_and(|e| e == "rs");
+            is_template.then(|| path.file_stem()?.to_str().map(String::from))?
+        }));
+    }
+
+    names.sort();
+    names.dedup();
+    names
 }
 
-pub fn handle(day: Day) {
-    let input_path = format!("data/inputs/{day}.txt");
-    let example_path = format!("data/examples/{day}.txt");
-    let module_path = format!("src/bin/{day}.rs");
+/// Replaces the variables of a template, e.g. `{{day}}`. Braces that don't enclose a name, like `{{}}` in a format string, are kept.
+fn render(template: &str, variables: &Variables) -> Result<String, TemplateError> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
 
-    let mut file = match safe_create_file(&module_path) {
-        Ok(file) => file,
-        Err(e) => {
-            eprintln!("Failed to create module file: {e}");
-            process::exit(1);
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+
+        let name = after.find("}}").map(|end| (after[..end].trim(), end));
+        match name {
+            Some((name, end))
+                if !name.is_empty()
+                    && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') =>
+            {
+                let value = variables
+                    .get(name)
+                    .ok_or_else(|| TemplateError::UnknownVariable(name.to_string()))?;
+                rendered.push_str(&value);
+                rest = &after[end + 2..];
+            }
+            _ => {
+                rendered.push_str("{{");
+                rest = after;
+            }
         }
-    };
+    }
+
+    rendered.push_str(rest);
+    Ok(rendered)
+}
+
+/// The title of the puzzle if its description was downloaded, e.g. `Trebuchet?!`.
+fn puzzle_title(day: Day) -> String {
+    fs::read_to_string(aoc_client::get_puzzle_path(day))
+        .ok()
+        .and_then(|description| puzzle::title(&description))
+        .unwrap_or_else(|| format!("Day {}", day.into_inner()))
+}
 
-    match file.write_all(
-        MODULE_TEMPLATE
-            .replace("DAY_NUMBER", &day.into_inner().to_string())
-            .as_bytes(),
-    ) {
-        Ok(()) => {
-            println!("Created module file \"{}\"", &module_path);
+fn path(folder: &str, file: &str) -> String {
+    data_dir(folder).join(file).display().to_string()
+}
+
+/// What scaffold found of an artifact before touching it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FileState {
+    Missing,
+    Empty,
+    Filled,
+}
+
+impl FileState {
+    fn of(path: &str) -> io::Result<Self> {
+        match fs::metadata(path) {
+            Ok(metadata) if metadata.len() == 0 => Ok(FileState::Empty),
+            Ok(_) => Ok(FileState::Filled),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(FileState::Missing),
+            Err(e) => Err(e),
         }
-        Err(e) => {
-            eprintln!("Failed to write module contents: {e}");
-            process::exit(1);
+    }
+}
+
+/// What scaffold did with an artifact, printed in the summary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Status {
+    Created,
+    CreatedEmpty,
+    Downloaded,
+    Exists,
+    ExistsEmpty,
+    /// Only downloaded with `--download`.
+    NotDownloaded,
+    Failed(String),
+}
+
+impl Status {
+    /// The status of a file that is created empty if missing, unless it was downloaded.
+    fn of_data_file(path: &str, is_downloaded: bool) -> Status {
+        match (is_downloaded, FileState::of(path)) {
+            (true, _) => Status::Downloaded,
+            (_, Ok(FileState::Filled)) => Status::Exists,
+            (_, Ok(FileState::Empty)) => Status::ExistsEmpty,
+            (_, Ok(FileState::Missing)) => match safe_create_file(path) {
+                Ok(_) => Status::CreatedEmpty,
+                Err(e) => Status::Failed(e.to_string()),
+            },
+            (_, Err(e)) => Status::Failed(e.to_string()),
         }
     }
 
-    match create_file(&input_path) {
-        Ok(_) => {
-            println!("Created empty input file \"{}\"", &input_path);
+    fn symbol(&self) -> &'static str {
+        match self {
+            Status::Created | Status::CreatedEmpty | Status::Downloaded => "✔",
+            Status::Exists | Status::ExistsEmpty => "•",
+            Status::NotDownloaded => "?",
+            Status::Failed(_) => "✖",
         }
-        Err(e) => {
-            eprintln!("Failed to create input file: {e}");
-            process::exit(1);
+    }
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Status::Created => write!(f, "created"),
+            Status::CreatedEmpty => write!(f, "created empty"),
+            Status::Downloaded => write!(f, "downloaded"),
+            Status::Exists => write!(f, "exists"),
+            Status::ExistsEmpty => write!(f, "exists, empty"),
+            Status::NotDownloaded => write!(f, "missing, append --download to fetch it"),
+            Status::Failed(e) => write!(f, "failed: {e}"),
         }
     }
+}
+
+fn safe_create_file(path: &str) -> Result<File, std::io::Error> {
+    OpenOptions::new().write(true).create_new(true).open(path)
+}
+
+/// Reads the template of a module and checks that it renders, so a misspelled name or variable fails before any file is written.
+fn resolve_template(name: &str, variables: &Variables) -> Result<String, TemplateError> {
+    let template = load_template(Path::new(TEMPLATE_DIR), name)?;
+    render(&template, variables)?;
+    Ok(template)
+}
+
+/// Renders the template and writes the module.
+fn create_module(path: &str, template: &str, variables: &Variables) -> Status {
+    let contents = match render(template, variables) {
+        Ok(contents) => contents,
+        Err(e) => return Status::Failed(e.to_string()),
+    };
+
+    match safe_create_file(path).and_then(|mut file| file.write_all(contents.as_bytes())) {
+        Ok(()) => Status::Created,
+        Err(e) => Status::Failed(e.to_string()),
+    }
+}
+
+type Download = fn(&AocClient, Day) -> Result<String, AocClientError>;
+
+/// The outcome of a download, [`None`] if the file was not needed.
+type Fetched = Option<Result<(), String>>;
+
+/// Downloads the input if it is missing or empty and the puzzle description if it is missing.
+/// Returns the outcome of each download that was attempted.
+fn download_missing(day: Day, input_path: &str, puzzle_path: &str) -> (Fetched, Fetched) {
+    let needs_input = !matches!(FileState::of(input_path), Ok(FileState::Filled));
+    let needs_puzzle = matches!(FileState::of(puzzle_path), Ok(FileState::Missing));
+    let client = AocClient::from_env().map_err(|e| e.to_string());
+
+    let fetch = |is_needed: bool, download: Download| {
+        is_needed.then(|| {
+            let client = client.as_ref().map_err(Clone::clone)?;
+            download(client, day).map(|_| ()).map_err(|e| e.to_string())
+        })
+    };
+
+    (
+        fetch(needs_input, aoc_client::download_input),
+        fetch(needs_puzzle, aoc_client::download_puzzle),
+    )
+}
+
+/// Creates whatever is missing of a day: its module, input and example files, and with `download` its input and puzzle description.
+/// Existing files are kept, so scaffolding a day again is safe.
+pub fn handle(day: Day, template: &str, answer_type: &str, download: bool) {
+    let input_path = aoc_client::get_input_path(day);
+    let example_path = path("examples", &format!("{day}.txt"));
+    let puzzle_path = aoc_client::get_puzzle_path(day);
+    let module_path = format!("src/bin/{}.rs", bin_name(year(), day));
 
-    match create_file(&example_path) {
-        Ok(_) => {
-            println!("Created empty example file \"{}\"", &example_path);
+    let mut variables = Variables {
+        day,
+        year: year(),
+        title: puzzle_title(day),
+        answer_type: answer_type.to_string(),
+    };
+
+    // the template of an existing module is never used.
+    let module_template = if Path::new(&module_path).exists() {
+        None
+    } else {
+        match resolve_template(template, &variables) {
+            Ok(template) => Some(template),
+            Err(e) => {
+                eprintln!("Failed to scaffold day {day}: {e}");
+                process::exit(1);
+            }
         }
-        Err(e) => {
-            eprintln!("Failed to create example file: {e}");
+    };
+
+    for folder in ["inputs", "examples"] {
+        if let Err(e) = fs::create_dir_all(data_dir(folder)) {
+            eprintln!("Failed to create data directory: {e}");
             process::exit(1);
         }
     }
 
+    // the puzzle is downloaded first, so the module can use its title.
+    let (input, puzzle) = if download {
+        download_missing(day, &input_path, &puzzle_path)
+    } else {
+        (None, None)
+    };
+
+    variables.title = puzzle_title(day);
+
+    let puzzle = match (puzzle, FileState::of(&puzzle_path)) {
+        (Some(Ok(())), _) => Status::Downloaded,
+        (Some(Err(e)), _) => Status::Failed(e),
+        (None, Ok(FileState::Missing)) => Status::NotDownloaded,
+        (None, Ok(_)) => Status::Exists,
+        (None, Err(e)) => Status::Failed(e.to_string()),
+    };
+
+    let input = match input {
+        Some(Err(e)) => Status::Failed(e),
+        downloaded => Status::of_data_file(&input_path, downloaded.is_some()),
+    };
+
+    let module = match &module_template {
+        Some(template) => create_module(&module_path, template, &variables),
+        None => Status::Exists,
+    };
+    let example = Status::of_data_file(&example_path, false);
+
+    let artifacts = [
+        ("module", module_path, module),
+        ("input", input_path, input),
+        ("example", example_path, example),
+        ("puzzle", puzzle_path, puzzle),
+    ];
+
+    for (name, path, status) in &artifacts {
+        println!("{} {name:<7} \"{path}\" {status}", status.symbol());
+    }
+
     println!("---");
+
+    if artifacts
+        .iter()
+        .any(|(_, _, status)| matches!(status, Status::Failed(_)))
+    {
+        eprintln!(
+            "Some files could not be created, scaffolding day {day} again only retries those."
+        );
+        process::exit(1);
+    }
+
     println!("🎄 Type `cargo solve {}` to run your solution.", day);
 }
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::{fs, path::Path};
+
+    use super::{
+        available_templates, load_template, render, resolve_template, FileState, Status,
+        TemplateError, Variables,
+    };
+    use crate::{day, Year};
+
+    fn variables() -> Variables {
+        Variables {
+            day: day!(12),
+            year: Year::new(2023).unwrap(),
+            title: "Hot Springs".into(),
+            answer_type: "u64".into(),
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let rendered = render(
+            "//! Day {{day}} of {{ year }}: {{title}}\nfn part_one() -> Option<{{answer_type}}> { format!(\"{{}}\") }",
+            &variables(),
+        )
+        .unwrap();
+        assert_eq!(
+            rendered,
+            "//! Day 12 of 2023: Hot Springs\nfn part_one() -> Option<u64> { format!(\"{{}}\") }"
+        );
+
+        assert!(matches!(
+            render("{{days}}", &variables()),
+            Err(TemplateError::UnknownVariable(name)) if name == "days"
+        ));
+    }
+
+    #[test]
+    fn test_built_in_templates() {
+        let dir = Path::new("does-not-exist");
+        let template = load_template(dir, "default").unwrap();
+        let rendered = render(&template, &variables()).unwrap();
+        assert!(rendered.starts_with("advent_of_code::solution!(12);"));
+        assert!(rendered.contains("-> Option<u64>"));
+
+        assert!(load_template(dir, "result").is_ok());
+        assert!(matches!(
+            load_template(dir, "grid"),
+            Err(TemplateError::NotFound { available, .. }) if available == ["default", "result"]
+        ));
+    }
+
+    #[test]
+    fn test_resolve_template() {
+        assert!(resolve_template("default", &variables()).is_ok());
+        assert!(matches!(
+            resolve_template("nope", &variables()),
+            Err(TemplateError::NotFound { name, .. }) if name == "nope"
+        ));
+    }
+
+    #[test]
+    fn test_project_templates() {
+        let dir = std::env::temp_dir().join(format!("aoc-templates-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("grid.rs"), "solution!({{day}}, parse);").unwrap();
+        fs::write(dir.join("default.rs"), "custom").unwrap();
+
+        assert_eq!(
+            load_template(&dir, "grid").unwrap(),
+            "solution!({{day}}, parse);"
+        );
+        assert_eq!(load_template(&dir, "default").unwrap(), "custom");
+        assert_eq!(available_templates(&dir), ["default", "grid", "result"]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_data_file_is_kept() {
+        let dir = std::env::temp_dir().join(format!("aoc-scaffold-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("12.txt").display().to_string();
+
+        assert_eq!(FileState::of(&path).unwrap(), FileState::Missing);
+        assert_eq!(Status::of_data_file(&path, false), Status::CreatedEmpty);
+        assert_eq!(Status::of_data_file(&path, false), Status::ExistsEmpty);
+
+        fs::write(&path, "1 2 3").unwrap();
+        assert_eq!(FileState::of(&path).unwrap(), FileState::Filled);
+        assert_eq!(Status::of_data_file(&path, false), Status::Exists);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1 2 3");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,23 +1,92 @@
-use std::process::{Command, Stdio};
+use std::{
+    path::PathBuf,
+    process::{self, Command, Stdio},
+};
 
+use crate::template::{
+    benchmark::BenchConfig,
+    bin_name,
+    runner::{Format, RunOptions},
+    year,
+};
 use crate::Day;
 
-pub fn handle(day: Day, release: bool, time: bool, submit_part: Option<u8>) {
-    let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), day.to_string()];
+/// Options of `solve` that are handled by the command itself, not by the solution's [`RunOptions`].
+#[derive(Debug, Clone, Default)]
+pub struct SolveOptions {
+    pub is_release: bool,
+    /// Submit the answer of this part after running.
+    pub submit: Option<u8>,
+    /// Submit even if the answer was already submitted.
+    pub force: bool,
+    /// The file that JSON records are written to.
+    pub output: Option<PathBuf>,
+    /// Inputs to run instead of the day's input file.
+    pub inputs: Vec<PathBuf>,
+}
+
+/// Runs the solution binary of `day`, passing `options` on to it.
+pub fn handle(day: Day, options: &RunOptions, solve: &SolveOptions) {
+    let mut cmd_args = vec![
+        "run".to_string(),
+        "--bin".to_string(),
+        bin_name(year(), day),
+    ];
 
-    if release {
+    if solve.is_release {
         cmd_args.push("--release".to_string());
     }
 
     cmd_args.push("--".to_string());
 
-    if let Some(submit_part) = submit_part {
+    if let Some(submit_part) = solve.submit {
         cmd_args.push("--submit".to_string());
         cmd_args.push(submit_part.to_string());
+
+        if solve.force {
+            cmd_args.push("--force".to_string());
+        }
     }
 
-    if time {
+    if options.is_timed {
         cmd_args.push("--time".to_string());
+        cmd_args.append(&mut bench_args(options.bench));
+    }
+
+    if options.verify {
+        cmd_args.push("--verify".to_string());
+    }
+
+    if options.format != Format::Human {
+        cmd_args.push("--format".to_string());
+        cmd_args.push(options.format.to_string());
+    }
+
+    if let Some(output) = &solve.output {
+        cmd_args.push("--output".to_string());
+        cmd_args.push(output.display().to_string());
+    }
+
+    let limits = options.limits;
+
+    if let Some(timeout) = limits.timeout {
+        cmd_args.push("--timeout".to_string());
+        cmd_args.push(timeout.as_millis().to_string());
+    }
+
+    if let Some(memory_mb) = limits.memory_mb {
+        cmd_args.push("--memory-limit".to_string());
+        cmd_args.push(memory_mb.to_string());
+    }
+
+    for input in &solve.inputs {
+        cmd_args.push("--input".to_string());
+        cmd_args.push(input.display().to_string());
+    }
+
+    for (name, value) in options.params.iter() {
+        cmd_args.push("--param".to_string());
+        cmd_args.push(format!("{name}={value}"));
     }
 
     let mut cmd = Command::new("cargo")
@@ -27,5 +96,31 @@ pub fn handle(day: Day, release: bool, time: bool, submit_part: Option<u8>) {
         .spawn()
         .unwrap();
 
-    cmd.wait().unwrap();
+    let status = cmd.wait().unwrap();
+    if !status.success() {
+        process::exit(status.code().unwrap_or(1));
+    }
+}
+
+/// Mirrors benchmark options that differ from the defaults to the child invocation.
+fn bench_args(bench: BenchConfig) -> Vec<String> {
+    let defaults = BenchConfig::default();
+    let mut args = vec![];
+
+    if bench.warmup_time != defaults.warmup_time {
+        args.push("--warmup-time".to_string());
+        args.push(bench.warmup_time.as_millis().to_string());
+    }
+
+    if bench.target_time != defaults.target_time {
+        args.push("--bench-time".to_string());
+        args.push(bench.target_time.as_millis().to_string());
+    }
+
+    if let Some(iterations) = bench.iterations {
+        args.push("--bench-iterations".to_string());
+        args.push(iterations.to_string());
+    }
+
+    args
 }
@@ -0,0 +1,292 @@
+//! Module that summarizes the progress of every day of the selected year in a single table.
+//! It only inspects files: the solution, input, examples and puzzle description of a day, its recorded answers and the latest benchmarks.
+//! Tests are only run with `--tests`, as that compiles every scaffolded day.
+use std::{fs, path::Path, process, time::Duration};
+
+use tinyjson::JsonValue;
+
+use crate::template::{
+    answers,
+    aoc_client::get_puzzle_path,
+    baseline::{baseline_path, Baseline, Store},
+    bin_name,
+    cargo::{self, TestSummary},
+    data_dir,
+    examples::is_example_of,
+    runner::{json_line, write_record, Format},
+    year, ANSI_BOLD, ANSI_RESET,
+};
+use crate::{Day, Days};
+
+/// The outcome of the example tests of a day.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Tests {
+    /// Tests only run with `--tests`.
+    NotRun,
+    BuildFailed,
+    Ran {
+        passed: usize,
+        failed: usize,
+    },
+}
+
+impl Tests {
+    fn run(bin: &str) -> Self {
+        let output = match cargo::run(&["test", "--bin", bin], false, &[]) {
+            Ok(output) => output,
+            Err(e) => {
+                eprintln!("Failed to run cargo: {e}");
+                process::exit(1);
+            }
+        };
+
+        match TestSummary::parse(&String::from_utf8_lossy(&output.stdout)) {
+            Some(summary) => Tests::Ran {
+                passed: summary.passed,
+                failed: summary.failed.len(),
+            },
+            None => Tests::BuildFailed,
+        }
+    }
+}
+
+/// What exists of a day.
+#[derive(Debug, Clone, PartialEq)]
+struct DayStatus {
+    day: Day,
+    is_scaffolded: bool,
+    has_input: bool,
+    /// The number of non-empty example inputs.
+    examples: usize,
+    has_puzzle: bool,
+    tests: Tests,
+    /// Whether an answer was recorded for each part.
+    stars: [bool; 2],
+    /// The total time of parsing and both parts in the latest benchmarks.
+    benchmark: Option<Duration>,
+}
+
+impl DayStatus {
+    fn collect(day: Day, baseline: Option<&Baseline>, run_tests: bool) -> Self {
+        let bin = bin_name(year(), day);
+        let is_scaffolded = Path::new(&format!("src/bin/{bin}.rs")).exists();
+
+        let tests = if run_tests && is_scaffolded {
+            Tests::run(&bin)
+        } else {
+            Tests::NotRun
+        };
+
+        let benchmark = baseline.and_then(|baseline| {
+            let timings: Vec<Duration> = baseline
+                .timings
+                .iter()
+                .filter(|t| t.day == day)
+                .map(|t| t.duration)
+                .collect();
+            (!timings.is_empty()).then(|| timings.iter().sum())
+        });
+
+        Self {
+            day,
+            is_scaffolded,
+            has_input: is_filled(&data_dir("inputs").join(format!("{day}.txt"))),
+            examples: count_examples(day),
+            has_puzzle: Path::new(&get_puzzle_path(day)).exists(),
+            tests,
+            stars: [1, 2].map(|part| answers::read(day, part).is_some()),
+            benchmark,
+        }
+    }
+
+    fn to_json(&self) -> String {
+        let (tests, passed, failed) = match self.tests {
+            Tests::NotRun => (JsonValue::Null, None, None),
+            Tests::BuildFailed => (JsonValue::String("build_failed".into()), None, None),
+            Tests::Ran { passed, failed } => {
+                let outcome = if failed == 0 { "passed" } else { "failed" };
+                (
+                    JsonValue::String(outcome.into()),
+                    Some(passed),
+                    Some(failed),
+                )
+            }
+        };
+        let count = |n: Option<usize>| n.map_or(JsonValue::Null, |n| JsonValue::Number(n as f64));
+
+        json_line(&[
+            ("day", JsonValue::Number(f64::from(self.day.into_inner()))),
+            ("scaffolded", JsonValue::Boolean(self.is_scaffolded)),
+            ("input", JsonValue::Boolean(self.has_input)),
+            ("examples", JsonValue::Number(self.examples as f64)),
+            ("puzzle", JsonValue::Boolean(self.has_puzzle)),
+            ("tests", tests),
+            ("tests_passed", count(passed)),
+            ("tests_failed", count(failed)),
+            (
+                "stars",
+                JsonValue::Array(self.stars.map(JsonValue::Boolean).to_vec()),
+            ),
+            (
+                "benchmark_nanos",
+                self.benchmark
+                    .map_or(JsonValue::Null, |d| JsonValue::Number(d.as_nanos() as f64)),
+            ),
+        ])
+    }
+
+    fn row(&self) -> String {
+        let check = |is_done: bool| if is_done { "✔" } else { "·" };
+
+        let examples = match self.examples {
+            0 => "·".to_string(),
+            count => count.to_string(),
+        };
+
+        let tests = match self.tests {
+            Tests::NotRun => "-".to_string(),
+            Tests::BuildFailed => "✖ build".to_string(),
+            Tests::Ran { passed, failed: 0 } => format!("✔ {passed}"),
+            Tests::Ran { passed, failed } => format!("✖ {failed}/{}", passed + failed),
+        };
+
+        let stars: String = self
+            .stars
+            .iter()
+            .map(|&star| if star { '★' } else { '☆' })
+            .collect();
+
+        let benchmark = self
+            .benchmark
+            .map_or("-".to_string(), |d| format!("{d:.1?}"));
+
+        format!(
+            "{:<4} {:<6} {:<6} {:<9} {:<7} {:<8} {:<6} {benchmark}",
+            self.day.to_string(),
+            check(self.is_scaffolded),
+            check(self.has_input),
+            examples,
+            check(self.has_puzzle),
+            tests,
+            stars,
+        )
+    }
+}
+
+fn is_filled(path: &Path) -> bool {
+    fs::metadata(path).is_ok_and(|m| m.len() > 0)
+}
+
+/// Counts the example inputs `NN.txt` and `NN-P.txt` of a day that aren't empty.
+fn count_examples(day: Day) -> usize {
+    let Ok(entries) = fs::read_dir(data_dir("examples")) else {
+        return 0;
+    };
+
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            is_example_of(path, day)
+                && path.extension().is_some_and(|e| e == "txt")
+                && is_filled(path)
+        })
+        .count()
+}
+
+pub fn handle(format: Format, run_tests: bool) {
+    let store = match Store::load(&baseline_path()) {
+        Ok(store) => store,
+        Err(e) => {
+            eprintln!("Could not read benchmarks: {e}");
+            Store::default()
+        }
+    };
+    let baseline = store.find(None);
+
+    let statuses: Vec<DayStatus> = Days::all()
+        .iter()
+        .map(|day| DayStatus::collect(day, baseline, run_tests))
+        .collect();
+
+    if format == Format::Json {
+        for status in &statuses {
+            write_record(&status.to_json());
+        }
+        return;
+    }
+
+    println!("{ANSI_BOLD}Advent of Code {}{ANSI_RESET}", year());
+    println!("Day  Code   Input  Examples  Puzzle  Tests    Stars  Benchmark");
+    println!("---  ----   -----  --------  ------  -----    -----  ---------");
+
+    for status in &statuses {
+        println!("{}", status.row());
+    }
+
+    let stars = statuses
+        .iter()
+        .flat_map(|s| s.stars)
+        .filter(|&star| star)
+        .count();
+    let scaffolded = statuses.iter().filter(|s| s.is_scaffolded).count();
+
+    println!();
+    println!("{stars}/50 stars, {scaffolded}/25 days scaffolded.");
+
+    if let Some(baseline) = baseline {
+        println!("Benchmarks from commit {}.", baseline.commit);
+    }
+
+    if !run_tests {
+        println!("Append --tests to run the example tests of every scaffolded day.");
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::time::Duration;
+
+    use super::{DayStatus, Tests};
+    use crate::day;
+
+    fn status() -> DayStatus {
+        DayStatus {
+            day: day!(3),
+            is_scaffolded: true,
+            has_input: true,
+            examples: 2,
+            has_puzzle: false,
+            tests: Tests::Ran {
+                passed: 3,
+                failed: 1,
+            },
+            stars: [true, false],
+            benchmark: Some(Duration::from_micros(1500)),
+        }
+    }
+
+    #[test]
+    fn test_row() {
+        assert_eq!(
+            status().row(),
+            "03   ✔      ✔      2         ·       ✖ 1/4    ★☆     1.5ms"
+        );
+    }
+
+    #[test]
+    fn test_to_json() {
+        assert_eq!(
+            status().to_json(),
+            r#"{"day":3,"scaffolded":true,"input":true,"examples":2,"puzzle":false,"tests":"failed","tests_passed":3,"tests_failed":1,"stars":[true,false],"benchmark_nanos":1500000}"#
+        );
+
+        let status = DayStatus {
+            tests: Tests::NotRun,
+            benchmark: None,
+            ..status()
+        };
+        assert!(status.to_json().contains(r#""tests":null"#));
+        assert!(status.to_json().ends_with(r#""benchmark_nanos":null}"#));
+    }
+}
@@ -0,0 +1,119 @@
+use std::process;
+
+use super::all::{print_day, Filter};
+use crate::template::{
+    answers::Verdict, registry::Registry, runner::RunOptions, try_read_file, year, ANSI_BOLD,
+    ANSI_RESET,
+};
+
+/// The verdict of every selected part of a day.
+type Verdicts = Vec<(u8, Verdict)>;
+
+/// Runs every scaffolded day and compares the answer of each part with the recorded one.
+/// Days and parts are selected with the same [`Filter`] as `all`. Exits with an error if any answer doesn't match.
+pub fn handle(registry: Registry, filter: &Filter) {
+    let options = RunOptions {
+        verify: true,
+        // failing days are printed once they completed, the others are left out.
+        is_quiet: filter.only_failing,
+        part: filter.part,
+        ..RunOptions::default()
+    };
+
+    let year = year();
+    let parts = filter.parts();
+    let mut summary: Vec<(String, Option<Verdicts>)> = vec![];
+
+    let days = filter.days(registry, year);
+
+    if days.is_empty() {
+        eprintln!("No days match the filters.");
+        return;
+    }
+
+    for day in days {
+        let Some(solution) = registry.get(year, day) else {
+            continue;
+        };
+
+        let print_header = |summary: &Vec<_>| {
+            if !summary.is_empty() {
+                println!();
+            }
+
+            println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
+            println!("------");
+        };
+
+        if !filter.only_failing {
+            print_header(&summary);
+        }
+
+        let verdicts = match try_read_file("inputs", day) {
+            Ok(input) => {
+                let result = solution.run(&input, &options);
+
+                if filter.only_failing {
+                    if !filter.is_failing(&result) {
+                        continue;
+                    }
+
+                    print_header(&summary);
+                    print_day(&result, filter, options.format);
+                }
+
+                Some(
+                    result
+                        .parts
+                        .into_iter()
+                        .filter(|part| parts.contains(&part.part))
+                        .map(|part| (part.part, part.verdict.unwrap_or(Verdict::Missing)))
+                        .collect(),
+                )
+            }
+            Err(e) => {
+                if filter.only_failing {
+                    print_header(&summary);
+                }
+
+                eprintln!("Could not read input file: {e}");
+                None
+            }
+        };
+
+        summary.push((day.to_string(), verdicts));
+    }
+
+    println!("\n{ANSI_BOLD}Summary{ANSI_RESET}");
+    println!("-------");
+
+    let mut counts = [0; 3];
+
+    for (day, verdicts) in &summary {
+        let Some(verdicts) = verdicts else {
+            println!("Day {day}: no input");
+            continue;
+        };
+
+        for (_, verdict) in verdicts {
+            counts[match verdict {
+                Verdict::Pass => 0,
+                Verdict::Fail { .. } => 1,
+                Verdict::Missing => 2,
+            }] += 1;
+        }
+
+        let parts: Vec<String> = verdicts
+            .iter()
+            .map(|(part, verdict)| format!("Part {part} {}", verdict.as_str()))
+            .collect();
+        println!("Day {day}: {}", parts.join(", "));
+    }
+
+    let [passed, failed, missing] = counts;
+    println!("\n{passed} passed, {failed} failed, {missing} missing.");
+
+    if failed > 0 {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,258 @@
+//! Module that reruns a day's example tests and solution whenever one of its files changes.
+//! Files are polled for changes, which works the same on every platform and doesn't need a dependency.
+use std::{
+    collections::{BTreeMap, HashMap},
+    env,
+    fmt::Display,
+    fs,
+    path::PathBuf,
+    process, thread,
+    time::{Duration, SystemTime},
+};
+
+use tinyjson::JsonValue;
+
+use crate::template::{
+    bin_name,
+    cargo::{self, TestSummary},
+    data_dir,
+    examples::is_example_of,
+    year, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET,
+};
+use crate::Day;
+
+/// How often the files of the day are checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(300);
+
+/// The number of compiler errors shown when the build fails.
+const MAX_BUILD_ERRORS: usize = 10;
+
+pub fn handle(day: Day, release: bool) {
+    let bin = bin_name(year(), day);
+
+    if !PathBuf::from(format!("src/bin/{bin}.rs")).exists() {
+        eprintln!("Day {day} is not scaffolded yet, run `cargo scaffold {day}` first.");
+        process::exit(1);
+    }
+
+    let mut previous = BTreeMap::new();
+
+    loop {
+        let current = snapshot(day, &bin);
+
+        if current != previous {
+            // editors often write a file in several steps, wait for them to settle.
+            thread::sleep(POLL_INTERVAL);
+            previous = snapshot(day, &bin);
+            run(day, &bin, release);
+        }
+
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// The modification time of every file the day depends on: its source, its input and its examples.
+fn snapshot(day: Day, bin: &str) -> BTreeMap<PathBuf, SystemTime> {
+    let mut files = vec![
+        PathBuf::from(format!("src/bin/{bin}.rs")),
+        data_dir("inputs").join(format!("{day}.txt")),
+    ];
+
+    if let Ok(entries) = fs::read_dir(data_dir("examples")) {
+        files.extend(
+            entries
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .filter(|path| is_example_of(path, day)),
+        );
+    }
+
+    files
+        .into_iter()
+        .filter_map(|path| {
+            let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+            Some((path, modified))
+        })
+        .collect()
+}
+
+fn run(day: Day, bin: &str, release: bool) {
+    // clears the terminal, so only the latest run is visible.
+    print!("\x1b[2J\x1b[H");
+    println!("{ANSI_BOLD}Day {day}{ANSI_RESET} {ANSI_ITALIC}watching for changes…{ANSI_RESET}");
+    println!("------");
+
+    let tests = match cargo::run(&["test", "--bin", bin], release, &[]) {
+        Ok(output) => output,
+        Err(e) => {
+            eprintln!("Failed to run cargo: {e}");
+            process::exit(1);
+        }
+    };
+
+    let stdout = String::from_utf8_lossy(&tests.stdout);
+    let stderr = String::from_utf8_lossy(&tests.stderr);
+    let Some(summary) = TestSummary::parse(&stdout) else {
+        println!("Build: ✖ failed");
+        for line in build_errors(&stderr) {
+            println!("  {line}");
+        }
+        return;
+    };
+
+    if summary.failed.is_empty() {
+        println!("Tests: ✔ {} passed", summary.passed);
+    } else {
+        println!(
+            "Tests: ✖ {} passed, {} failed",
+            summary.passed,
+            summary.failed.len()
+        );
+        for name in &summary.failed {
+            println!("  {ANSI_ITALIC}{name}{ANSI_RESET}");
+        }
+        println!("Skipping the input until the tests pass.");
+        return;
+    }
+
+    if !data_dir("inputs").join(format!("{day}.txt")).exists() {
+        println!("Input: ? missing, run `cargo download {day}`");
+        return;
+    }
+
+    // the records go to a file, as the solution may print to stdout itself.
+    let records_path = env::temp_dir().join(format!("aoc-watch-{bin}.jsonl"));
+    let _ = fs::remove_file(&records_path);
+
+    if let Err(e) = cargo::run(
+        &["run", "--bin", bin],
+        release,
+        &[
+            "--format",
+            "json",
+            "--output",
+            &records_path.display().to_string(),
+            "--verify",
+        ],
+    ) {
+        eprintln!("Failed to run cargo: {e}");
+        process::exit(1);
+    }
+
+    let records = fs::read_to_string(&records_path).unwrap_or_default();
+    for record in records.lines().filter_map(PartRecord::parse) {
+        println!("{record}");
+    }
+}
+
+/// Compiler errors and their locations, e.g. `error[E0308]: mismatched types` followed by ` --> src/bin/2023-05.rs:12:9`.
+fn build_errors(stderr: &str) -> Vec<&str> {
+    let mut lines = vec![];
+    let mut errors = 0;
+
+    for line in stderr.lines() {
+        if line.starts_with("error") && !line.starts_with("error: could not compile") {
+            errors += 1;
+            if errors > MAX_BUILD_ERRORS {
+                break;
+            }
+            lines.push(line);
+        } else if line.trim_start().starts_with("-->") && errors > 0 {
+            lines.push(line.trim_start());
+        }
+    }
+
+    lines
+}
+
+/// A part as reported by `--format json`.
+#[derive(Debug, PartialEq)]
+struct PartRecord {
+    part: u8,
+    answer: Option<String>,
+    duration: Duration,
+    status: String,
+    verdict: Option<String>,
+    error: Option<String>,
+}
+
+impl PartRecord {
+    /// Lines that aren't a part, like the parse phase or output of the solution itself, are skipped.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn parse(line: &str) -> Option<Self> {
+        let json: JsonValue = line.parse().ok()?;
+        let fields = json.get::<HashMap<String, JsonValue>>()?;
+        let number = |key: &str| fields.get(key)?.get::<f64>().copied();
+        let string = |key: &str| fields.get(key)?.get::<String>().cloned();
+
+        Some(Self {
+            part: number("part")? as u8,
+            answer: string("answer"),
+            duration: Duration::from_nanos(number("duration_nanos")? as u64),
+            status: string("status")?,
+            verdict: string("verdict"),
+            error: string("error"),
+        })
+    }
+}
+
+impl Display for PartRecord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Part {}: ", self.part)?;
+
+        match (&self.answer, &self.error) {
+            (Some(answer), _) if answer.contains('\n') => {
+                write!(f, "▼ ({:.1?})\n{answer}", self.duration)?;
+            }
+            (Some(answer), _) => {
+                write!(f, "{ANSI_BOLD}{answer}{ANSI_RESET} ({:.1?})", self.duration)?
+            }
+            (None, Some(error)) => {
+                write!(f, "✖ {ANSI_ITALIC}{}: {error}{ANSI_RESET}", self.status)?
+            }
+            (None, None) => write!(f, "✖ {}", self.status)?,
+        }
+
+        match self.verdict.as_deref() {
+            Some("pass") => write!(f, " ✔"),
+            Some("fail") => write!(
+                f,
+                " {ANSI_ITALIC}✖ doesn't match the recorded answer{ANSI_RESET}"
+            ),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::time::Duration;
+
+    use super::{build_errors, PartRecord};
+
+    #[test]
+    fn test_build_errors() {
+        let stderr = "   Compiling advent_of_code v0.9.3\nerror[E0308]: mismatched types\n  --> src/bin/2023-05.rs:12:9\n   |\nerror: could not compile `advent_of_code`";
+        assert_eq!(
+            build_errors(stderr),
+            vec![
+                "error[E0308]: mismatched types",
+                "--> src/bin/2023-05.rs:12:9"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_part_record() {
+        let record = PartRecord::parse(r#"{"day":5,"part":1,"answer":"35","duration_nanos":1500,"samples":1,"status":"solved","verdict":"pass","error":null}"#).unwrap();
+        assert_eq!(record.answer.as_deref(), Some("35"));
+        assert_eq!(record.duration, Duration::from_nanos(1500));
+        assert_eq!(record.verdict.as_deref(), Some("pass"));
+
+        assert_eq!(
+            PartRecord::parse(r#"{"day":3,"phase":"parse","duration_nanos":1}"#),
+            None
+        );
+        assert_eq!(PartRecord::parse("Calculating 4 destinations"), None);
+    }
+}
@@ -0,0 +1,343 @@
+//! Module that extracts example inputs and their expected answers from a puzzle description.
+//! Examples are found heuristically: a `<pre><code>` block introduced by a paragraph mentioning "example" is an example input,
+//! and the last emphasized code (e.g. `<code><em>42</em></code>`) of a part is that part's answer for the most recent example.
+//! Example manifests (`data/YYYY/examples/NN.toml`) are written by [`update_manifest`] and turned into tests by `build.rs`, see [`assert_answer`].
+use std::{env, fs, path::Path};
+
+use crate::template::data_dir;
+use crate::template::puzzle::{self, Node};
+use crate::template::runner::Failure;
+use crate::Day;
+
+/// An example input with the expected answer of each part, if the puzzle states one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Example {
+    pub input: String,
+    pub answers: [Option<String>; 2],
+}
+
+enum Event {
+    Paragraph(String),
+    Block(String),
+    Answer(String),
+}
+
+/// Extracts the examples of a puzzle description, i.e. the html of its `<article>` elements.
+/// Blocks that no answer refers to, such as intermediate states, are skipped.
+pub fn extract(html: &str) -> Vec<Example> {
+    let nodes = puzzle::parse(html);
+    let mut articles = vec![];
+    find_articles(&nodes, &mut articles);
+
+    let mut examples: Vec<Example> = vec![];
+    // part two usually refers back to the example of part one.
+    let mut current = None;
+
+    for (part, article) in articles.iter().take(2).enumerate() {
+        let mut events = vec![];
+        walk(article, &mut events);
+
+        let mut is_introduced = false;
+        let mut answer = None;
+
+        for event in events {
+            match event {
+                Event::Paragraph(text) => {
+                    is_introduced = text.to_lowercase().contains("example");
+                }
+                Event::Block(input) if is_introduced => {
+                    let index = match examples.iter().position(|e| e.input == input) {
+                        Some(index) => index,
+                        None => {
+                            examples.push(Example {
+                                input,
+                                answers: [None, None],
+                            });
+                            examples.len() - 1
+                        }
+                    };
+                    current = Some(index);
+                    is_introduced = false;
+                }
+                Event::Block(_) => {}
+                Event::Answer(text) => {
+                    answer = current.map(|index| (index, text));
+                }
+            }
+        }
+
+        if let Some((index, text)) = answer {
+            examples[index].answers[part] = Some(text);
+        }
+    }
+
+    examples.retain(|e| e.answers.iter().any(Option::is_some));
+    examples
+}
+
+fn find_articles<'a>(nodes: &'a [Node], articles: &mut Vec<&'a Node>) {
+    for node in nodes {
+        if let Node::Element { name, children, .. } = node {
+            if name == "article" {
+                articles.push(node);
+            } else {
+                find_articles(children, articles);
+            }
+        }
+    }
+}
+
+/// Flattens the parts of an article relevant for extraction, in document order.
+fn walk(node: &Node, events: &mut Vec<Event>) {
+    let Node::Element { name, children, .. } = node else {
+        return;
+    };
+
+    match name.as_str() {
+        "pre" => events.push(Event::Block(node.text())),
+        "code" if node.contains("em") => events.push(Event::Answer(node.text().trim().into())),
+        "em" if node.contains("code") => events.push(Event::Answer(node.text().trim().into())),
+        "code" | "em" => {}
+        _ => {
+            if name == "p" {
+                events.push(Event::Paragraph(node.text()));
+            }
+            for child in children {
+                walk(child, events);
+            }
+        }
+    }
+}
+
+/// Name of the file of an example, following the conventions of [`crate::template::read_file`] and [`crate::template::read_file_part`].
+pub fn file_name(day: Day, index: usize, count: usize) -> String {
+    if count == 1 {
+        format!("{day}.txt")
+    } else {
+        format!("{day}-{}.txt", index + 1)
+    }
+}
+
+/// The file and part of every `[[example]]` entry of a manifest, parsed like `build.rs` does, see there for the format.
+fn listed_entries(manifest: &str) -> Result<Vec<(String, u8)>, String> {
+    let manifest: toml::Table = manifest.parse().map_err(|e| format!("{e}"))?;
+
+    let examples = match manifest.get("example") {
+        Some(toml::Value::Array(examples)) => examples.as_slice(),
+        Some(_) => return Err("`example` must be an array of tables".into()),
+        None => &[],
+    };
+
+    Ok(examples
+        .iter()
+        .filter_map(|example| {
+            let file = example.get("file")?.as_str()?;
+            let part = u8::try_from(example.get("part")?.as_integer()?).ok()?;
+            Some((file.to_string(), part))
+        })
+        .collect())
+}
+
+/// Adds an entry for every answer of the examples to a manifest `data/YYYY/examples/NN.toml`, which `build.rs` turns into tests.
+/// Entries that are already listed for the same file and part are left alone, so answers corrected by hand are kept.
+/// Returns the updated manifest and the file and part of every added entry, or an error if the manifest is not valid TOML.
+pub fn update_manifest(
+    manifest: &str,
+    day: Day,
+    examples: &[Example],
+) -> Result<(String, Vec<(String, u8)>), String> {
+    let listed = listed_entries(manifest)?;
+    let mut manifest = manifest.trim_end().to_string();
+    let mut added = vec![];
+
+    for (i, example) in examples.iter().enumerate() {
+        let file = file_name(day, i, examples.len());
+
+        for (part, answer) in (1..).zip(&example.answers) {
+            let Some(answer) = answer else {
+                continue;
+            };
+
+            if listed.iter().any(|(f, p)| *f == file && *p == part) {
+                continue;
+            }
+
+            // numbers are written as integers, anything else as a string.
+            let answer = match answer.parse::<i64>() {
+                Ok(answer) => toml::Value::Integer(answer),
+                Err(_) => toml::Value::String(answer.clone()),
+            };
+
+            if !manifest.is_empty() {
+                manifest.push_str("\n\n");
+            }
+            manifest.push_str(&format!(
+                "[[example]]\nfile = {}\npart = {part}\nanswer = {answer}",
+                toml::Value::String(file.clone())
+            ));
+            added.push((file.clone(), part));
+        }
+    }
+
+    // entries can't be appended to examples written as an inline array.
+    if let Err(e) = manifest.parse::<toml::Table>() {
+        return Err(format!("could not add entries to the manifest: {e}"));
+    }
+
+    Ok((manifest + "\n", added))
+}
+
+/// Matches `NN.txt`, `NN-P.txt` and the manifest `NN.toml`.
+pub fn is_example_of(path: &Path, day: Day) -> bool {
+    let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else {
+        return false;
+    };
+
+    let day = day.to_string();
+    stem == day || stem.strip_prefix(&day).is_some_and(|s| s.starts_with('-'))
+}
+
+/// Reads an example file listed in a manifest.
+#[must_use]
+pub fn read(file: &str) -> String {
+    let filepath = env::current_dir()
+        .unwrap()
+        .join(data_dir("examples"))
+        .join(file);
+    fs::read_to_string(filepath).expect("could not open example file")
+}
+
+/// Asserts that a part's answer for an example matches the manifest. Called by the tests generated from the manifest.
+#[track_caller]
+pub fn assert_answer(
+    answer: Result<Option<String>, Failure>,
+    file: &str,
+    part: u8,
+    expected: &str,
+) {
+    let answer = answer
+        .unwrap_or_else(|failure| panic!("part {part} of example {file} failed with {failure}"));
+    assert_eq!(
+        answer.as_deref(),
+        Some(expected),
+        "wrong answer for part {part} of example {file}"
+    );
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::path::Path;
+
+    use super::{extract, file_name, is_example_of, update_manifest, Example};
+    use crate::day;
+
+    const TWO_EXAMPLES: &str = "<article><p>For example:</p><pre><code>1\n2\n</code></pre><p>After one step:</p><pre><code>3\n</code></pre><p>The sum is <code>3</code>, so the answer is <code><em>3</em></code>.</p></article><article><p>Here is another example:</p><pre><code>4\n</code></pre><p>This <em>doubles</em> to <em><code>8</code></em>.</p></article>";
+
+    #[test]
+    fn test_extract_fixture() {
+        assert_eq!(
+            extract(include_str!("fixtures/puzzle.html")),
+            vec![Example {
+                input: "1 < 2\n3 & 4\n".into(),
+                answers: [Some("10".into()), None],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_extract_saved_page() {
+        assert_eq!(
+            extract(include_str!("fixtures/page.html")),
+            vec![Example {
+                input: "3 1 4\n1 5\n9 2 6\n".into(),
+                answers: [Some("17".into()), Some("27".into())],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_extract_per_part() {
+        assert_eq!(
+            extract(TWO_EXAMPLES),
+            vec![
+                Example {
+                    input: "1\n2\n".into(),
+                    answers: [Some("3".into()), None],
+                },
+                Example {
+                    input: "4\n".into(),
+                    answers: [None, Some("8".into())],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_is_example_of() {
+        let day = day!(5);
+        assert!(is_example_of(Path::new("data/2023/examples/05.txt"), day));
+        assert!(is_example_of(Path::new("data/2023/examples/05-2.txt"), day));
+        assert!(is_example_of(Path::new("data/2023/examples/05.toml"), day));
+        assert!(!is_example_of(Path::new("data/2023/examples/15.txt"), day));
+        assert!(!is_example_of(Path::new("data/2023/examples/.keep"), day));
+    }
+
+    #[test]
+    fn test_file_name() {
+        assert_eq!(file_name(day!(8), 0, 1), "08.txt");
+        assert_eq!(file_name(day!(8), 2, 3), "08-3.txt");
+    }
+
+    #[test]
+    fn test_update_manifest() {
+        let (manifest, added) = update_manifest("", day!(8), &extract(TWO_EXAMPLES)).unwrap();
+        assert_eq!(
+            manifest,
+            "[[example]]\nfile = \"08-1.txt\"\npart = 1\nanswer = 3\n\n[[example]]\nfile = \"08-2.txt\"\npart = 2\nanswer = 8\n"
+        );
+        assert_eq!(added, [("08-1.txt".into(), 1), ("08-2.txt".into(), 2)]);
+
+        // listed entries are kept, even if their answer differs.
+        let manifest = "[[example]]\nfile = \"08-1.txt\"\npart = 1\nanswer = 4\n";
+        let examples = [Example {
+            input: "a\n".into(),
+            answers: [Some("3".into()), Some("abc".into())],
+        }];
+        let (manifest, added) = update_manifest(
+            manifest,
+            day!(8),
+            &[examples[0].clone(), examples[0].clone()],
+        )
+        .unwrap();
+        assert_eq!(
+            manifest,
+            "[[example]]\nfile = \"08-1.txt\"\npart = 1\nanswer = 4\n\n[[example]]\nfile = \"08-1.txt\"\npart = 2\nanswer = \"abc\"\n\n[[example]]\nfile = \"08-2.txt\"\npart = 1\nanswer = 3\n\n[[example]]\nfile = \"08-2.txt\"\npart = 2\nanswer = \"abc\"\n"
+        );
+        assert_eq!(added.len(), 3);
+        assert!(update_manifest(
+            &manifest,
+            day!(8),
+            &[examples[0].clone(), examples[0].clone()]
+        )
+        .unwrap()
+        .1
+        .is_empty());
+
+        // entries are recognized regardless of comments, quoting and inline tables.
+        let two = [examples[0].clone(), examples[0].clone()];
+        let manifest = "# day 8\n[[example]] # first\nfile = '08-1.txt'\npart = 1 # corrected by hand\nanswer = 4\nparams = { expansion = 10 }\n\n[[other]]\nfile = \"08-2.txt\"\npart = 1\n";
+        let (_, added) = update_manifest(manifest, day!(8), &two).unwrap();
+        assert_eq!(
+            added,
+            [
+                ("08-1.txt".into(), 2),
+                ("08-2.txt".into(), 1),
+                ("08-2.txt".into(), 2)
+            ]
+        );
+
+        assert!(update_manifest("[[example]\n", day!(8), &examples).is_err());
+        let inline = "example = [{ file = '08-1.txt', part = 1, answer = 4 }]\n";
+        assert!(update_manifest(inline, day!(8), &two).is_err());
+    }
+}
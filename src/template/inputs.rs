@@ -0,0 +1,277 @@
+//! Module that runs a solution against several inputs at once, e.g. inputs shared by teammates to cross-check solutions.
+//! Inputs are passed with `--input`, which accepts files as well as directories such as `data/2023/inputs/05/`.
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+    process,
+};
+
+use tinyjson::JsonValue;
+
+use crate::template::registry::Solution;
+use crate::template::runner::{
+    catch_panic, json_line, warn_unmatched_params, write_record, Failure, Format, RunOptions,
+};
+use crate::template::{ANSI_BOLD, ANSI_RESET};
+
+/// The outcome of running a part against one of the inputs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Solved(String),
+    Unsolved,
+    /// The part panicked or returned an error.
+    Failed(Failure),
+    /// The input could not be read, neither part was run.
+    Unreadable(String),
+}
+
+impl Outcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Solved(_) => "solved",
+            Self::Unsolved => "unsolved",
+            Self::Failed(failure) => failure.as_str(),
+            Self::Unreadable(_) => "unreadable",
+        }
+    }
+
+    /// Whether the input deserves attention, i.e. the part didn't produce an answer.
+    pub fn is_flagged(&self) -> bool {
+        !matches!(self, Self::Solved(_))
+    }
+
+    fn cell(&self) -> String {
+        match self {
+            Self::Solved(answer) if answer.contains('\n') => "▼ multi-line".into(),
+            Self::Solved(answer) => answer.clone(),
+            Self::Unsolved => "✖ unsolved".into(),
+            Self::Failed(failure) => format!("✖ {}", failure.as_str()),
+            Self::Unreadable(_) => "✖ unreadable".into(),
+        }
+    }
+}
+
+/// Reads the values of every `--input` passed to a solution binary, [`None`] if there are none.
+pub fn from_args() -> Option<Vec<PathBuf>> {
+    let args: Vec<String> = env::args().collect();
+
+    let paths: Vec<PathBuf> = args
+        .windows(2)
+        .filter(|w| w[0] == "--input")
+        .map(|w| PathBuf::from(&w[1]))
+        .collect();
+
+    (!paths.is_empty()).then_some(paths)
+}
+
+/// Expands directories to the files they contain, sorted by name. Hidden files like `.keep` are skipped.
+pub fn expand(paths: &[PathBuf]) -> io::Result<Vec<PathBuf>> {
+    let mut files = vec![];
+
+    for path in paths {
+        if !path.is_dir() {
+            files.push(path.clone());
+            continue;
+        }
+
+        let mut entries: Vec<PathBuf> = fs::read_dir(path)?
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file() && !is_hidden(path))
+            .collect();
+        entries.sort();
+        files.extend(entries);
+    }
+
+    Ok(files)
+}
+
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with('.'))
+}
+
+/// Runs both parts against every input and prints a comparison table, or one JSON record per input and part.
+/// Exits with an error if a part failed or an input could not be read.
+pub fn run(solution: &dyn Solution, paths: &[PathBuf]) {
+    let options = RunOptions::from_args();
+
+    if options.limits.is_set() {
+        eprintln!("--timeout and --memory-limit can't be combined with --input, run the inputs one at a time instead.");
+        process::exit(1);
+    }
+
+    let files = match expand(paths) {
+        Ok(files) if !files.is_empty() => files,
+        Ok(_) => {
+            eprintln!("No input files found.");
+            process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("Could not read input directory: {e}");
+            process::exit(1);
+        }
+    };
+
+    let rows: Vec<(String, [Outcome; 2])> = files
+        .iter()
+        .map(|file| {
+            let outcomes = match fs::read_to_string(file) {
+                Ok(input) => [1, 2].map(|part| {
+                    match options
+                        .params
+                        .apply(|| catch_panic(|| solution.answer(part, &input)))
+                    {
+                        Ok(Ok(Some(answer))) => Outcome::Solved(answer),
+                        Ok(Ok(None)) => Outcome::Unsolved,
+                        Ok(Err(failure)) => Outcome::Failed(failure),
+                        Err(message) => Outcome::Failed(Failure::Panicked(message)),
+                    }
+                }),
+                Err(e) => [(); 2].map(|_| Outcome::Unreadable(e.to_string())),
+            };
+            (file.display().to_string(), outcomes)
+        })
+        .collect();
+
+    match options.format {
+        Format::Human => print_table(&rows),
+        Format::Json => {
+            for (input, outcomes) in &rows {
+                for (part, outcome) in (1..).zip(outcomes) {
+                    write_record(&to_json(solution, input, part, outcome));
+                }
+            }
+        }
+    }
+
+    warn_unmatched_params();
+
+    let is_failure = rows
+        .iter()
+        .flat_map(|(_, o)| o)
+        .any(|o| matches!(o, Outcome::Failed(_) | Outcome::Unreadable(_)));
+
+    if is_failure {
+        process::exit(1);
+    }
+}
+
+/// Serializes the outcome to a single line of JSON, e.g.
+/// `{"day":5,"part":1,"input":"data/2023/inputs/05/a.txt","answer":"42","status":"solved","error":null}`.
+fn to_json(solution: &dyn Solution, input: &str, part: u8, outcome: &Outcome) -> String {
+    let answer = match outcome {
+        Outcome::Solved(answer) => JsonValue::String(answer.clone()),
+        _ => JsonValue::Null,
+    };
+
+    let error = match outcome {
+        Outcome::Failed(failure) => JsonValue::String(failure.message()),
+        Outcome::Unreadable(e) => JsonValue::String(e.clone()),
+        _ => JsonValue::Null,
+    };
+
+    json_line(&[
+        (
+            "day",
+            JsonValue::Number(f64::from(solution.day().into_inner())),
+        ),
+        ("part", JsonValue::Number(f64::from(part))),
+        ("input", JsonValue::String(input.into())),
+        ("answer", answer),
+        ("status", JsonValue::String(outcome.as_str().into())),
+        ("error", error),
+    ])
+}
+
+fn print_table(rows: &[(String, [Outcome; 2])]) {
+    let cells: Vec<[String; 3]> = rows
+        .iter()
+        .map(|(input, [one, two])| [input.clone(), one.cell(), two.cell()])
+        .collect();
+
+    let header = ["Input", "Part 1", "Part 2"].map(String::from);
+    let widths: Vec<usize> = (0..3)
+        .map(|i| {
+            cells
+                .iter()
+                .chain([&header])
+                .map(|row| row[i].chars().count())
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+
+    let line = |row: &[String; 3]| {
+        row.iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    println!("{ANSI_BOLD}{}{ANSI_RESET}", line(&header));
+    for row in &cells {
+        println!("{}", line(row));
+    }
+
+    let mut flagged = 0;
+    for (input, outcomes) in rows {
+        if outcomes.iter().any(Outcome::is_flagged) {
+            flagged += 1;
+        }
+
+        for (part, outcome) in (1..).zip(outcomes) {
+            match outcome {
+                Outcome::Failed(failure) => println!("{input}: part {part} {failure}"),
+                Outcome::Unreadable(e) if part == 1 => println!("{input}: could not read: {e}"),
+                _ => {}
+            }
+        }
+    }
+
+    println!("\n{} input(s), {flagged} flagged.", rows.len());
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use super::{expand, Outcome};
+    use crate::template::runner::Failure;
+
+    #[test]
+    fn test_expand() {
+        let dir = std::env::temp_dir().join(format!("aoc-inputs-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for name in ["b.txt", "a.txt", ".keep"] {
+            fs::write(dir.join(name), "").unwrap();
+        }
+
+        let file = PathBuf::from("data/2023/inputs/05.txt");
+        assert_eq!(
+            expand(&[dir.clone(), file.clone()]).unwrap(),
+            vec![dir.join("a.txt"), dir.join("b.txt"), file]
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_outcome_cell() {
+        assert_eq!(Outcome::Solved("42".into()).cell(), "42");
+        assert_eq!(Outcome::Solved("#.\n.#".into()).cell(), "▼ multi-line");
+        assert_eq!(
+            Outcome::Failed(Failure::Panicked("boom".into())).cell(),
+            "✖ panicked"
+        );
+        assert_eq!(
+            Outcome::Failed(Failure::Error(vec!["no path".into()])).cell(),
+            "✖ error"
+        );
+        assert!(Outcome::Unsolved.is_flagged());
+    }
+}
@@ -0,0 +1,694 @@
+//! Module that enforces per-part limits on wall-clock time and memory, e.g. `cargo solve 5 --timeout 10000`.
+//! A limited day runs in a child process of the current binary, which reports its progress to a file while the parent watches
+//! the part that is running. The memory of the child is measured on its own, so other days running concurrently don't count
+//! against a part. A part that exceeds a limit is killed together with its process, and the remaining parts run in a fresh one.
+use std::{
+    env,
+    fs::{self, File, OpenOptions},
+    io::{self, Read, Write},
+    path::Path,
+    process::{self, Child, Command, ExitStatus, Stdio},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex, OnceLock,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use tinyjson::JsonValue;
+
+use crate::template::params;
+use crate::template::registry::Solution;
+use crate::template::runner::{
+    arg_value, exit_on_failure, json_line, print_parse, print_part, submit_result,
+    warn_unmatched_params, DayResult, Failure, ParseResult, PartResult, Record, RunOptions,
+};
+use crate::Day;
+
+/// How often the parent checks the running part.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Set in the environment of a child process to the file it reports its progress to.
+const EVENTS_VAR: &str = "AOC_LIMITS_EVENTS";
+
+/// Limits that apply to a single run of parsing or a part. When benched, the runs after the first are not limited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Limits {
+    pub timeout: Option<Duration>,
+    /// Maximum growth of the resident set size of the child process while the part runs, in megabytes. Only enforced on Linux.
+    pub memory_mb: Option<u64>,
+}
+
+impl Limits {
+    pub fn is_set(&self) -> bool {
+        self.timeout.is_some() || self.memory_mb.is_some()
+    }
+
+    /// Fails a run that took longer than the timeout, e.g. one that finished between two checks of [`Limits::check`].
+    fn check_duration(&self, duration: Duration) -> Option<Failure> {
+        let timeout = self.timeout?;
+        (duration > timeout).then_some(Failure::TimedOut(timeout))
+    }
+
+    fn check(
+        &self,
+        started: Instant,
+        rss_before: Option<u64>,
+        rss: Option<u64>,
+    ) -> Option<Failure> {
+        if let Some(timeout) = self.timeout {
+            if started.elapsed() > timeout {
+                return Some(Failure::TimedOut(timeout));
+            }
+        }
+
+        let limit = self.memory_mb?;
+        let grown = rss?.saturating_sub(rss_before?);
+        (grown > limit * 1024 * 1024).then_some(Failure::OutOfMemory(limit))
+    }
+}
+
+/// The phase of a day that is running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    Parse,
+    Part(u8),
+}
+
+/// Progress reported by a child process, one JSON object per line of its events file.
+#[derive(Debug, Clone)]
+enum Event {
+    /// A stage started, with the resident set size of the child at that point.
+    Started(Stage, Option<u64>),
+    /// The first run of the current stage finished after the given time, benchmark runs are not watched.
+    Ran(Duration),
+    Parse(ParseResult),
+    Part(PartResult),
+    /// The names of the parameters whose override was read, sent once the day completed.
+    Params(Vec<String>),
+}
+
+/// The connection of a child process to its parent.
+struct Reporter {
+    events: Mutex<File>,
+    /// Parts up to this one already ran in a process that was killed.
+    skip_until: u8,
+}
+
+static REPORTER: OnceLock<Reporter> = OnceLock::new();
+
+/// Reports an event to the parent, if this is a child process.
+/// Exits if the event can't be written, as the parent would wait for it in vain.
+fn send(event: &Event) {
+    let Some(reporter) = REPORTER.get() else {
+        return;
+    };
+
+    let mut events = reporter.events.lock().unwrap_or_else(|e| e.into_inner());
+    if let Err(e) = writeln!(events, "{}", event.to_json()) {
+        eprintln!("Failed to report progress: {e}");
+        process::exit(1);
+    }
+}
+
+/// Marks the start of a stage. Returns `false` if the stage already ran in a process that was killed,
+/// in which case a part is skipped and parsing runs without being watched or reported.
+pub(crate) fn start(stage: Stage) -> bool {
+    let is_skipped = REPORTER.get().is_some_and(|r| match stage {
+        Stage::Parse => r.skip_until > 0,
+        Stage::Part(part) => part <= r.skip_until,
+    });
+
+    if !is_skipped {
+        send(&Event::Started(stage, resident_set_size(process::id())));
+    }
+
+    !is_skipped
+}
+
+pub(crate) fn ran(duration: Duration) {
+    send(&Event::Ran(duration));
+}
+
+pub(crate) fn finish_parse(result: &ParseResult) {
+    send(&Event::Parse(result.clone()));
+}
+
+pub(crate) fn finish_part(result: &PartResult) {
+    send(&Event::Part(result.clone()));
+}
+
+/// Runs a day, failing parsing or a part that exceeds the limits in `options`.
+/// Without limits, the day runs in the current process.
+pub fn run(solution: &'static dyn Solution, input: &str, options: &RunOptions) -> DayResult {
+    if !options.limits.is_set() {
+        return solution.run(input, options);
+    }
+
+    let day = solution.day();
+    let mut parse = None;
+    let mut parts = [1, 2].map(|part| PartResult::skipped(day, part));
+    let mut skip_until = 0;
+
+    loop {
+        let watched = watch(day, input, options, skip_until);
+
+        parse = parse.or(watched.parse);
+        for result in watched.parts {
+            let index = usize::from(result.part - 1);
+            parts[index] = result;
+        }
+
+        match watched.exceeded {
+            Some((Stage::Parse, failure)) => {
+                let result = ParseResult {
+                    day,
+                    duration: Duration::ZERO,
+                    samples: 0,
+                    stats: None,
+                    error: Some(failure.clone()),
+                };
+                if !options.is_quiet {
+                    print_parse(&result, options.format);
+                }
+                parse = Some(result);
+
+                for part in [1, 2] {
+                    let result = PartResult::failed(day, part, failure.clone());
+                    if !options.is_quiet {
+                        print_part(&result, options.format);
+                    }
+                    parts[usize::from(part - 1)] = result;
+                }
+                break;
+            }
+            Some((Stage::Part(part), failure)) => {
+                let result = PartResult::failed(day, part, failure);
+                if !options.is_quiet {
+                    print_part(&result, options.format);
+                }
+                parts[usize::from(part - 1)] = result;
+                skip_until = part;
+            }
+            None => break,
+        }
+
+        // the remaining part was not selected.
+        if skip_until >= 2 || options.part.is_some() {
+            break;
+        }
+    }
+
+    DayResult { parse, parts }
+}
+
+/// Runs a solution binary with limits, submits answers if `--submit` is passed and exits with an error if a part failed.
+pub fn run_main(solution: &'static dyn Solution, input: &str) {
+    let result = run(solution, input, &RunOptions::from_args());
+
+    if result.parse.as_ref().is_some_and(|p| p.error.is_some()) {
+        process::exit(1);
+    }
+
+    for part in &result.parts {
+        if let Some(answer) = &part.answer {
+            submit_result(answer, part.day, part.part);
+        }
+    }
+
+    warn_unmatched_params();
+    exit_on_failure(&result.parts);
+}
+
+/// The day to run if this process is the child process of [`run`], which is passed with `--day`.
+pub fn child_day() -> Option<Day> {
+    env::var_os(EVENTS_VAR)?;
+    arg_value(&env::args().collect::<Vec<_>>(), "--day")
+}
+
+/// Runs the day as the child process of [`run`], reading the input from stdin, then exits.
+pub fn run_child(solution: &'static dyn Solution) -> ! {
+    let args: Vec<String> = env::args().collect();
+    let Some(events_path) = env::var_os(EVENTS_VAR) else {
+        eprintln!("{EVENTS_VAR} is not set.");
+        process::exit(1);
+    };
+
+    let events = match OpenOptions::new().append(true).open(&events_path) {
+        Ok(events) => events,
+        Err(e) => {
+            eprintln!("Failed to open {}: {e}", Path::new(&events_path).display());
+            process::exit(1);
+        }
+    };
+
+    let mut input = String::new();
+    if let Err(e) = io::stdin().read_to_string(&mut input) {
+        eprintln!("Failed to read the input: {e}");
+        process::exit(1);
+    }
+
+    let options = RunOptions {
+        is_quiet: args.iter().any(|x| x == "--quiet"),
+        part: arg_value(&args, "--part"),
+        ..RunOptions::parse(&args)
+    };
+
+    let _ = REPORTER.set(Reporter {
+        events: Mutex::new(events),
+        skip_until: arg_value(&args, "--skip-until").unwrap_or(0),
+    });
+
+    solution.run(&input, &options);
+    send(&Event::Params(params::matched()));
+    process::exit(0);
+}
+
+/// The arguments of a child process, mirroring `options` without the limits.
+fn child_args(day: Day, options: &RunOptions, skip_until: u8) -> Vec<String> {
+    let mut args = vec![
+        "--day".to_string(),
+        day.to_string(),
+        "--skip-until".to_string(),
+        skip_until.to_string(),
+        "--warmup-time".to_string(),
+        options.bench.warmup_time.as_millis().to_string(),
+        "--bench-time".to_string(),
+        options.bench.target_time.as_millis().to_string(),
+    ];
+
+    if let Some(iterations) = options.bench.iterations {
+        args.push("--bench-iterations".to_string());
+        args.push(iterations.to_string());
+    }
+
+    if let Some(part) = options.part {
+        args.push("--part".to_string());
+        args.push(part.to_string());
+    }
+
+    // results are printed by the parent, which knows whether they exceeded a limit.
+    args.push("--quiet".to_string());
+
+    for (flag, is_set) in [("--time", options.is_timed), ("--verify", options.verify)] {
+        if is_set {
+            args.push(flag.to_string());
+        }
+    }
+
+    for (name, value) in options.params.iter() {
+        args.push("--param".to_string());
+        args.push(format!("{name}={value}"));
+    }
+
+    args
+}
+
+#[derive(Default)]
+struct Watched {
+    parse: Option<ParseResult>,
+    parts: Vec<PartResult>,
+    exceeded: Option<(Stage, Failure)>,
+}
+
+/// Runs the day in a child process until it finishes or a stage exceeds the limits, which kills the process.
+fn watch(day: Day, input: &str, options: &RunOptions, skip_until: u8) -> Watched {
+    static CHILDREN: AtomicUsize = AtomicUsize::new(0);
+
+    let events_path = env::temp_dir().join(format!(
+        "aoc-limits-{}-{}.jsonl",
+        process::id(),
+        CHILDREN.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    let watched = File::create(&events_path)
+        .and_then(|_| File::open(&events_path))
+        .and_then(|events| {
+            let child = spawn(day, input, options, skip_until, &events_path)?;
+            Ok(watch_child(child, events, options, skip_until))
+        })
+        .unwrap_or_else(|e| {
+            eprintln!("Failed to run day {day} in a child process: {e}");
+            process::exit(1);
+        });
+
+    let _ = fs::remove_file(&events_path);
+    watched
+}
+
+fn spawn(
+    day: Day,
+    input: &str,
+    options: &RunOptions,
+    skip_until: u8,
+    events_path: &Path,
+) -> io::Result<Child> {
+    let mut child = Command::new(env::current_exe()?)
+        .args(child_args(day, options, skip_until))
+        .env(EVENTS_VAR, events_path)
+        .stdin(Stdio::piped())
+        .spawn()?;
+
+    // a child that exits before reading its input closes the pipe, which is reported once it exited.
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(input.as_bytes());
+    }
+
+    Ok(child)
+}
+
+fn watch_child(
+    mut child: Child,
+    mut events: File,
+    options: &RunOptions,
+    skip_until: u8,
+) -> Watched {
+    let mut watched = Watched::default();
+    let mut current: Option<(Stage, Instant, Option<u64>)> = None;
+    // the stage that started last and whether its result was reported, to blame a crash on.
+    let mut last: Option<(Stage, bool)> = None;
+    let mut pending = String::new();
+
+    loop {
+        // events are read after checking for an exit, so none written before the exit are missed.
+        let exited = child.try_wait();
+
+        let mut read = String::new();
+        let _ = events.read_to_string(&mut read);
+        pending.push_str(&read);
+
+        while let Some(end) = pending.find('\n') {
+            let line: String = pending.drain(..=end).collect();
+            match Event::parse(&line) {
+                Some(Event::Started(stage, rss)) => {
+                    current = Some((stage, Instant::now(), rss));
+                    last = Some((stage, false));
+                }
+                Some(Event::Ran(duration)) => {
+                    if let Some((stage, ..)) = current {
+                        if let Some(failure) = options.limits.check_duration(duration) {
+                            kill(&mut child);
+                            watched.exceeded = Some((stage, failure));
+                            return watched;
+                        }
+                    }
+                    current = None;
+                }
+                Some(Event::Parse(result)) => {
+                    if !options.is_quiet {
+                        print_parse(&result, options.format);
+                    }
+                    last = last.map(|(stage, _)| (stage, true));
+                    watched.parse = Some(result);
+                }
+                Some(Event::Part(result)) => {
+                    if !options.is_quiet {
+                        print_part(&result, options.format);
+                    }
+                    last = last.map(|(stage, _)| (stage, true));
+                    watched.parts.push(result);
+                }
+                Some(Event::Params(names)) => names.iter().for_each(|n| params::mark_matched(n)),
+                None => {}
+            }
+        }
+
+        match exited {
+            Ok(Some(status)) if status.success() => return watched,
+            Ok(Some(status)) => {
+                watched.exceeded =
+                    crashed_stage(last, options, skip_until).map(|stage| (stage, crash(status)));
+                return watched;
+            }
+            Ok(None) => {}
+            Err(e) => {
+                eprintln!("Failed to wait for the child process: {e}");
+                process::exit(1);
+            }
+        }
+
+        if let Some((stage, started, rss_before)) = current {
+            let rss = resident_set_size(child.id());
+            if let Some(failure) = options.limits.check(started, rss_before, rss) {
+                kill(&mut child);
+                watched.exceeded = Some((stage, failure));
+                return watched;
+            }
+        }
+
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+fn kill(child: &mut Child) {
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// The stage a crash is blamed on: the one that was running, or the next one if the last stage completed.
+fn crashed_stage(
+    last: Option<(Stage, bool)>,
+    options: &RunOptions,
+    skip_until: u8,
+) -> Option<Stage> {
+    let next = options.part.unwrap_or(skip_until + 1);
+
+    match last {
+        Some((stage, false)) => Some(stage),
+        Some((Stage::Part(part), true)) if part >= 2 || options.part.is_some() => None,
+        Some((Stage::Part(part), true)) => Some(Stage::Part(part + 1)),
+        Some((Stage::Parse, true)) | None => Some(Stage::Part(next)),
+    }
+}
+
+fn crash(status: ExitStatus) -> Failure {
+    Failure::Panicked(format!("the process crashed ({status})"))
+}
+
+/// The resident set size of a process in bytes, read from `/proc/<pid>/status`.
+fn resident_set_size(pid: u32) -> Option<u64> {
+    let status = fs::read_to_string(format!("/proc/{pid}/status")).ok()?;
+    let line = status.lines().find(|l| l.starts_with("VmRSS:"))?;
+    let kilobytes: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kilobytes * 1024)
+}
+
+impl Event {
+    /// Results are written as the JSON records of the runner, other events are tagged with `event`.
+    fn to_json(&self) -> String {
+        let number = |n: u128| JsonValue::Number(n as f64);
+
+        match self {
+            Event::Started(stage, rss) => {
+                let part = match stage {
+                    Stage::Parse => JsonValue::Null,
+                    Stage::Part(part) => number(u128::from(*part)),
+                };
+                json_line(&[
+                    ("event", JsonValue::String("started".into())),
+                    ("part", part),
+                    ("rss", rss.map_or(JsonValue::Null, |rss| number(rss.into()))),
+                ])
+            }
+            Event::Ran(duration) => json_line(&[
+                ("event", JsonValue::String("ran".into())),
+                ("duration_nanos", number(duration.as_nanos())),
+            ]),
+            Event::Parse(result) => result.to_json(),
+            Event::Part(result) => result.to_json(),
+            Event::Params(names) => json_line(&[
+                ("event", JsonValue::String("params".into())),
+                (
+                    "matched",
+                    JsonValue::Array(names.iter().cloned().map(JsonValue::String).collect()),
+                ),
+            ]),
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn parse(line: &str) -> Option<Self> {
+        let record = Record::parse(line)?;
+
+        Some(match record.str("event") {
+            Some("started") => {
+                let stage = match record.number("part") {
+                    Some(part) => Stage::Part(part as u8),
+                    None => Stage::Parse,
+                };
+                Event::Started(stage, record.number("rss").map(|n| n as u64))
+            }
+            Some("ran") => Event::Ran(record.duration("duration_nanos")?),
+            Some("params") => Event::Params(record.strings("matched")?),
+            Some(_) => return None,
+            None => match ParseResult::from_record(&record) {
+                Some(result) => Event::Parse(result),
+                None => Event::Part(PartResult::from_record(&record)?),
+            },
+        })
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{crashed_stage, Event, Limits, Stage};
+    use crate::day;
+    use crate::template::answers::Verdict;
+    use crate::template::benchmark::BenchStats;
+    use crate::template::runner::{Failure, ParseResult, PartResult, RunOptions};
+
+    #[test]
+    fn test_check_timeout() {
+        let limits = Limits {
+            timeout: Some(Duration::from_millis(50)),
+            memory_mb: None,
+        };
+        let started = Instant::now();
+        assert_eq!(limits.check(started, None, None), None);
+
+        let started = started - Duration::from_millis(100);
+        assert_eq!(
+            limits.check(started, None, None),
+            Some(Failure::TimedOut(Duration::from_millis(50)))
+        );
+    }
+
+    #[test]
+    fn test_check_duration() {
+        let limits = Limits {
+            timeout: Some(Duration::from_millis(1)),
+            memory_mb: None,
+        };
+        assert_eq!(limits.check_duration(Duration::from_micros(500)), None);
+        assert_eq!(
+            limits.check_duration(Duration::from_micros(3500)),
+            Some(Failure::TimedOut(Duration::from_millis(1)))
+        );
+        assert_eq!(
+            Limits::default().check_duration(Duration::from_secs(60)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_check_memory() {
+        let limits = Limits {
+            timeout: None,
+            memory_mb: Some(1),
+        };
+        let started = Instant::now();
+        assert_eq!(limits.check(started, Some(1 << 20), Some(2 << 20)), None);
+        assert_eq!(
+            limits.check(started, Some(1 << 20), Some(3 << 20)),
+            Some(Failure::OutOfMemory(1))
+        );
+        assert_eq!(limits.check(started, None, Some(3 << 20)), None);
+    }
+
+    #[test]
+    fn test_event_round_trip() {
+        let stats = BenchStats {
+            median: Duration::from_nanos(150),
+            mean: Duration::from_nanos(160),
+            min: Duration::from_nanos(100),
+            max: Duration::from_nanos(300),
+            p95: Duration::from_nanos(280),
+            stddev: Duration::from_nanos(20),
+            samples: 98,
+            outliers: 2,
+        };
+        let part = PartResult {
+            day: day!(5),
+            part: 2,
+            answer: Some("42".into()),
+            duration: Duration::from_nanos(150),
+            samples: 100,
+            stats: Some(stats),
+            verdict: Some(Verdict::Fail {
+                expected: "43".into(),
+            }),
+            error: Some(Failure::Error(vec!["invalid seed".into(), "empty".into()])),
+        };
+
+        let Some(Event::Part(parsed)) = Event::parse(&Event::Part(part.clone()).to_json()) else {
+            panic!("expected a part");
+        };
+        assert_eq!(parsed.answer, part.answer);
+        assert_eq!(parsed.samples, 100);
+        assert_eq!(parsed.stats, Some(stats));
+        assert_eq!(parsed.verdict, part.verdict);
+        assert_eq!(parsed.error, part.error);
+
+        let timed_out = Event::Part(PartResult::failed(
+            day!(5),
+            1,
+            Failure::TimedOut(Duration::from_secs(2)),
+        ));
+        let Some(Event::Part(parsed)) = Event::parse(&timed_out.to_json()) else {
+            panic!("expected a part");
+        };
+        assert_eq!(
+            parsed.error,
+            Some(Failure::TimedOut(Duration::from_secs(2)))
+        );
+        assert_eq!(parsed.answer, None);
+
+        let Some(Event::Parse(parsed)) = Event::parse(
+            &Event::Parse(ParseResult {
+                day: day!(5),
+                duration: Duration::ZERO,
+                samples: 0,
+                stats: None,
+                error: Some(Failure::OutOfMemory(64)),
+            })
+            .to_json(),
+        ) else {
+            panic!("expected parsing");
+        };
+        assert_eq!(parsed.error, Some(Failure::OutOfMemory(64)));
+        assert_eq!(parsed.stats, None);
+
+        assert!(matches!(
+            Event::parse(&Event::Started(Stage::Part(1), Some(4096)).to_json()),
+            Some(Event::Started(Stage::Part(1), Some(4096)))
+        ));
+        assert!(matches!(
+            Event::parse(&Event::Ran(Duration::from_micros(3500)).to_json()),
+            Some(Event::Ran(d)) if d == Duration::from_micros(3500)
+        ));
+        assert!(Event::parse("not json").is_none());
+    }
+
+    #[test]
+    fn test_crashed_stage() {
+        let options = RunOptions::default();
+        assert_eq!(
+            crashed_stage(Some((Stage::Part(1), false)), &options, 0),
+            Some(Stage::Part(1))
+        );
+        assert_eq!(
+            crashed_stage(Some((Stage::Part(1), true)), &options, 0),
+            Some(Stage::Part(2))
+        );
+        assert_eq!(
+            crashed_stage(Some((Stage::Part(2), true)), &options, 0),
+            None
+        );
+        assert_eq!(crashed_stage(None, &options, 1), Some(Stage::Part(2)));
+    }
+
+    #[test]
+    fn test_failure_message() {
+        assert_eq!(
+            Failure::TimedOut(Duration::from_secs(5)).to_string(),
+            "timed out after 5.0s"
+        );
+        assert_eq!(
+            Failure::OutOfMemory(512).message(),
+            "exceeded the memory limit of 512MB"
+        );
+    }
+}
@@ -1,48 +1,240 @@
-use crate::Day;
-use std::{env, fs};
+use crate::{Day, Year};
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
 
-pub mod aoc_cli;
+pub mod answers;
+pub mod aoc_client;
+pub mod baseline;
+pub mod benchmark;
+pub mod cargo;
 pub mod commands;
+pub mod examples;
+pub mod inputs;
+pub mod limits;
+pub mod params;
+pub mod puzzle;
+pub mod readme;
 pub mod readme_benchmarks;
+pub mod registry;
 pub mod runner;
+pub mod submissions;
 
 pub const ANSI_ITALIC: &str = "\x1b[3m";
 pub const ANSI_BOLD: &str = "\x1b[1m";
 pub const ANSI_RESET: &str = "\x1b[0m";
 
+/// The year being solved, [`None`] if it can't be determined.
+/// A solution binary is named after its year and day (e.g. `2023-01`) and always uses that year, whether it runs or is tested.
+/// Anything else, like the main binary, uses `AOC_YEAR`, which `--year` overrides.
+pub fn try_year() -> Option<Year> {
+    let from_bin = env::current_exe().ok().and_then(|exe| {
+        let name = exe.file_stem()?.to_str()?.to_string();
+        Year::from_bin_name(&name)
+    });
+
+    from_bin.or_else(|| env::var("AOC_YEAR").ok()?.parse().ok())
+}
+
+/// Like [`try_year`], but panics if the year can't be determined.
+#[must_use]
+pub fn year() -> Year {
+    try_year().expect("could not determine the year, set AOC_YEAR or pass --year")
+}
+
+/// Directory of a kind of data for the current year, e.g. `data/2023/inputs`.
+#[must_use]
+pub fn data_dir(folder: &str) -> PathBuf {
+    Path::new("data").join(year().to_string()).join(folder)
+}
+
+/// Name of the binary of a day's solution, e.g. `2023-01` for `src/bin/2023-01.rs`.
+#[must_use]
+pub fn bin_name(year: Year, day: Day) -> String {
+    format!("{year}-{day}")
+}
+
 /// Helper function that reads a text file to a string.
 #[must_use]
 pub fn read_file(folder: &str, day: Day) -> String {
-    let cwd = env::current_dir().unwrap();
-    let filepath = cwd.join("data").join(folder).join(format!("{day}.txt"));
-    let f = fs::read_to_string(filepath);
-    f.expect("could not open input file")
+    try_read_file(folder, day).expect("could not open input file")
+}
+
+/// Helper function that reads a text file to a string, returning an error instead of panicking if it can't be read.
+pub fn try_read_file(folder: &str, day: Day) -> io::Result<String> {
+    let cwd = env::current_dir()?;
+    let filepath = cwd.join(data_dir(folder)).join(format!("{day}.txt"));
+    fs::read_to_string(filepath)
 }
 
 /// Helper function that reads a text file to string, appending a part suffix. E.g. like `01-2.txt`.
 #[must_use]
 pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
     let cwd = env::current_dir().unwrap();
-    let filepath = cwd
-        .join("data")
-        .join(folder)
-        .join(format!("{day}-{part}.txt"));
+    let filepath = cwd.join(data_dir(folder)).join(format!("{day}-{part}.txt"));
     let f = fs::read_to_string(filepath);
     f.expect("could not open input file")
 }
 
 /// Creates the constant `DAY` and sets up the input and runner for each part.
+/// Also creates the constant `SOLUTION`, which registers the day for in-process execution via `cargo all`.
+///
+/// Passing a parse function as second argument, e.g. `solution!(3, parse)`, parses the input once and hands
+/// the result to both parts. Parsing is then timed separately from the parts.
+///
+/// Parts may return an [`Option`], a [`Result`] or a plain answer, see [`runner::PartOutput`].
+/// Values that differ between the examples and the real input are declared with [`param!`](crate::param!) instead of `#[cfg(test)]` constants.
 #[macro_export]
 macro_rules! solution {
+    (@register $run:expr, $answer:expr) => {
+        /// Registry entry for the current day.
+        #[allow(dead_code)]
+        pub const SOLUTION: &dyn advent_of_code::template::registry::Solution = &__Solution;
+
+        // Not part of the public API
+        #[doc(hidden)]
+        pub struct __Solution;
+
+        impl advent_of_code::template::registry::Solution for __Solution {
+            fn day(&self) -> advent_of_code::Day {
+                DAY
+            }
+
+            fn run(
+                &self,
+                input: &str,
+                options: &advent_of_code::template::runner::RunOptions,
+            ) -> advent_of_code::template::runner::DayResult {
+                ($run)(input, options)
+            }
+
+            fn answer(
+                &self,
+                part: u8,
+                input: &str,
+            ) -> Result<Option<String>, advent_of_code::template::runner::Failure> {
+                ($answer)(part, input)
+            }
+        }
+    };
+    (@examples) => {
+        /// Tests generated from the example manifest `data/YYYY/examples/NN.toml`.
+        #[cfg(test)]
+        mod examples {
+            use super::*;
+
+            #[allow(dead_code)]
+            #[track_caller]
+            fn check(part: u8, file: &str, expected: &str, params: &[(&str, &str)]) {
+                let input = advent_of_code::template::examples::read(file);
+                let params: advent_of_code::template::params::Params = params.iter().copied().collect();
+                let answer = params.apply(|| {
+                    advent_of_code::template::registry::Solution::answer(&__Solution, part, &input)
+                });
+                advent_of_code::template::examples::assert_answer(answer, file, part, expected);
+            }
+
+            include!(concat!(env!("OUT_DIR"), "/examples/", env!("CARGO_BIN_NAME"), ".rs"));
+        }
+    };
     ($day:expr) => {
         /// The current day.
         const DAY: advent_of_code::Day = advent_of_code::day!($day);
 
         fn main() {
             use advent_of_code::template::runner::*;
+            if advent_of_code::template::limits::child_day().is_some() {
+                advent_of_code::template::limits::run_child(SOLUTION);
+            }
+            if let Some(paths) = advent_of_code::template::inputs::from_args() {
+                advent_of_code::template::inputs::run(SOLUTION, &paths);
+                return;
+            }
+            let input = advent_of_code::template::read_file("inputs", DAY);
+            if RunOptions::from_args().limits.is_set() {
+                advent_of_code::template::limits::run_main(SOLUTION, &input);
+                return;
+            }
+            let parts = [
+                run_part(part_one, &input, DAY, 1),
+                run_part(part_two, &input, DAY, 2),
+            ];
+            warn_unmatched_params();
+            exit_on_failure(&parts);
+        }
+
+        $crate::solution!(@register |input: &str, options: &advent_of_code::template::runner::RunOptions| {
+            use advent_of_code::template::runner::*;
+            DayResult {
+                parse: None,
+                parts: [
+                    solve_part(part_one, input, DAY, 1, options),
+                    solve_part(part_two, input, DAY, 2, options),
+                ],
+            }
+        }, |part: u8, input: &str| {
+            use advent_of_code::template::runner::PartOutput;
+            match part {
+                1 => part_one(input).into_answer(),
+                _ => part_two(input).into_answer(),
+            }
+        });
+
+        $crate::solution!(@examples);
+    };
+    ($day:expr, $parse:ident) => {
+        /// The current day.
+        const DAY: advent_of_code::Day = advent_of_code::day!($day);
+
+        fn main() {
+            use advent_of_code::template::runner::*;
+            if advent_of_code::template::limits::child_day().is_some() {
+                advent_of_code::template::limits::run_child(SOLUTION);
+            }
+            if let Some(paths) = advent_of_code::template::inputs::from_args() {
+                advent_of_code::template::inputs::run(SOLUTION, &paths);
+                return;
+            }
             let input = advent_of_code::template::read_file("inputs", DAY);
-            run_part(part_one, &input, DAY, 1);
-            run_part(part_two, &input, DAY, 2);
+            if RunOptions::from_args().limits.is_set() {
+                advent_of_code::template::limits::run_main(SOLUTION, &input);
+                return;
+            }
+            let parsed = run_parse($parse, &input, DAY);
+            let parts = [
+                run_part(part_one, &parsed, DAY, 1),
+                run_part(part_two, &parsed, DAY, 2),
+            ];
+            warn_unmatched_params();
+            exit_on_failure(&parts);
         }
+
+        $crate::solution!(@register |input: &str, options: &advent_of_code::template::runner::RunOptions| {
+            use advent_of_code::template::runner::*;
+            let (parsed, parse) = solve_parse($parse, input, DAY, options);
+            let parts = match &parsed {
+                Some(parsed) => [
+                    solve_part(part_one, parsed, DAY, 1, options),
+                    solve_part(part_two, parsed, DAY, 2, options),
+                ],
+                None => [1, 2].map(|part| {
+                    PartResult::failed(DAY, part, Failure::Panicked("parsing failed".into()))
+                }),
+            };
+            DayResult {
+                parse: Some(parse),
+                parts,
+            }
+        }, |part: u8, input: &str| {
+            use advent_of_code::template::runner::PartOutput;
+            let parsed = $parse(input);
+            match part {
+                1 => part_one(&parsed).into_answer(),
+                _ => part_two(&parsed).into_answer(),
+            }
+        });
+
+        $crate::solution!(@examples);
     };
 }
@@ -0,0 +1,199 @@
+//! Typed parameters of a solution, e.g. a factor that differs between the examples and the real input.
+//! A parameter is declared with [`crate::param!`] and has a default for the real input.
+//! Overrides are passed with `--param name=value` or listed in the example manifest, and apply to the current thread while a part runs.
+use std::{cell::RefCell, fmt::Display, str::FromStr, sync::Mutex};
+
+thread_local! {
+    static OVERRIDES: RefCell<Params> = RefCell::new(Params::default());
+}
+
+/// The names of the parameters that were overridden while a part ran, see [`Params::unmatched`].
+static MATCHED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Records that an override of `name` was read, e.g. by a part that ran in a child process.
+pub(crate) fn mark_matched(name: &str) {
+    let mut matched = MATCHED.lock().unwrap_or_else(|e| e.into_inner());
+    if !matched.iter().any(|m| m == name) {
+        matched.push(name.to_string());
+    }
+}
+
+/// The names of the parameters whose override was read so far.
+pub(crate) fn matched() -> Vec<String> {
+    MATCHED.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Overrides of parameter values by name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Params(Vec<(String, String)>);
+
+impl Params {
+    /// Later values take precedence over earlier ones for the same name.
+    pub fn insert(&mut self, name: &str, value: &str) {
+        self.0.retain(|(n, _)| !n.eq_ignore_ascii_case(name));
+        self.0.push((name.into(), value.into()));
+    }
+
+    /// Names are matched case-insensitively, so `expansion` overrides `EXPANSION`.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0.iter().map(|(n, v)| (n.as_str(), v.as_str()))
+    }
+
+    /// The names of the overrides that didn't match a parameter the solution read so far, e.g. because of a typo.
+    pub fn unmatched(&self) -> Vec<&str> {
+        let matched = MATCHED.lock().unwrap_or_else(|e| e.into_inner());
+        self.0
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .filter(|name| !matched.iter().any(|m| m.eq_ignore_ascii_case(name)))
+            .collect()
+    }
+
+    /// Runs `f` with these overrides in effect on the current thread.
+    pub fn apply<R>(&self, f: impl FnOnce() -> R) -> R {
+        let previous = OVERRIDES.with(|o| o.replace(self.clone()));
+        let result = f();
+        OVERRIDES.with(|o| o.replace(previous));
+        result
+    }
+}
+
+impl<'a> FromIterator<(&'a str, &'a str)> for Params {
+    fn from_iter<I: IntoIterator<Item = (&'a str, &'a str)>>(iter: I) -> Self {
+        let mut params = Params::default();
+        for (name, value) in iter {
+            params.insert(name, value);
+        }
+        params
+    }
+}
+
+/// A single `name=value` override, as passed on the command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Override {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug)]
+pub struct OverrideFromStrError;
+
+impl std::error::Error for OverrideFromStrError {}
+
+impl Display for OverrideFromStrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("expecting a parameter in the form `name=value`")
+    }
+}
+
+impl FromStr for Override {
+    type Err = OverrideFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('=') {
+            Some((name, value)) if !name.trim().is_empty() => Ok(Self {
+                name: name.trim().into(),
+                value: value.trim().into(),
+            }),
+            _ => Err(OverrideFromStrError),
+        }
+    }
+}
+
+impl Display for Override {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}={}", self.name, self.value)
+    }
+}
+
+/// A named parameter with a default value, see [`crate::param!`].
+pub struct Param<T> {
+    name: &'static str,
+    default: T,
+}
+
+impl<T: Clone + FromStr> Param<T> {
+    pub const fn new(name: &'static str, default: T) -> Self {
+        Self { name, default }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The overridden value, or the default if there is no override.
+    /// This looks up and parses the override on every call, so read the value once instead of in a loop.
+    /// Panics if the override can't be parsed.
+    pub fn get(&self) -> T {
+        let value = OVERRIDES.with(|o| o.borrow().get(self.name).map(str::to_string));
+
+        match value {
+            Some(value) => {
+                mark_matched(self.name);
+                value.parse().unwrap_or_else(|_| {
+                    panic!("invalid value \"{value}\" for parameter \"{}\"", self.name)
+                })
+            }
+            None => self.default.clone(),
+        }
+    }
+}
+
+/// Declares a parameter of a solution. Overrides refer to it by its name in any case.
+///
+/// ```ignore
+/// advent_of_code::param!(EXPANSION: usize = 1_000_000);
+///
+/// pub fn part_two(input: &str) -> Option<u64> {
+///     solve(input, EXPANSION.get())
+/// }
+/// ```
+///
+/// The example above can be overridden with `cargo solve 11 --param expansion=10`.
+#[macro_export]
+macro_rules! param {
+    ($name:ident: $ty:ty = $default:expr) => {
+        static $name: $crate::template::params::Param<$ty> =
+            $crate::template::params::Param::new(stringify!($name), $default);
+    };
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Override, Param, Params};
+
+    #[test]
+    fn test_override_from_str() {
+        assert_eq!(
+            "expansion = 10".parse::<Override>().unwrap(),
+            Override {
+                name: "expansion".into(),
+                value: "10".into()
+            }
+        );
+        assert!("expansion".parse::<Override>().is_err());
+        assert!("=10".parse::<Override>().is_err());
+    }
+
+    #[test]
+    fn test_param() {
+        static EXPANSION: Param<usize> = Param::new("EXPANSION", 1_000_000);
+        assert_eq!(EXPANSION.get(), 1_000_000);
+
+        let params: Params = [("expansion", "10"), ("other", "x")].into_iter().collect();
+        assert_eq!(params.apply(|| EXPANSION.get()), 10);
+        assert_eq!(EXPANSION.get(), 1_000_000);
+        assert_eq!(params.unmatched(), ["other"]);
+    }
+}
@@ -0,0 +1,531 @@
+//! Converts puzzle descriptions from html to markdown or to styled text for the terminal.
+//! The parser only handles the small, well-formed subset of html used for the `<article>` elements of a puzzle page.
+use crate::template::{ANSI_BOLD, ANSI_RESET};
+
+/// An element that is still open while parsing: its name, attributes and the children parsed so far.
+type OpenElement = (String, Vec<(String, String)>, Vec<Node>);
+
+/// Elements that never have children or a closing tag.
+const VOID_ELEMENTS: [&str; 6] = ["br", "hr", "img", "input", "link", "meta"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Node {
+    Text(String),
+    Element {
+        name: String,
+        attrs: Vec<(String, String)>,
+        children: Vec<Node>,
+    },
+}
+
+impl Node {
+    fn attr(&self, key: &str) -> Option<&str> {
+        match self {
+            Node::Element { attrs, .. } => attrs
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.as_str()),
+            Node::Text(_) => None,
+        }
+    }
+
+    pub(crate) fn text(&self) -> String {
+        match self {
+            Node::Text(text) => text.clone(),
+            Node::Element { children, .. } => children.iter().map(Node::text).collect(),
+        }
+    }
+
+    pub(crate) fn contains(&self, element: &str) -> bool {
+        match self {
+            Node::Text(_) => false,
+            Node::Element { children, .. } => children.iter().any(|child| {
+                matches!(child, Node::Element { name, .. } if name == element)
+                    || child.contains(element)
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Target {
+    Markdown,
+    Terminal,
+}
+
+/// Converts a puzzle description to markdown.
+pub fn to_markdown(html: &str) -> String {
+    render(html, Target::Markdown)
+}
+
+/// Converts a puzzle description to text with ANSI styles, for printing to a terminal.
+pub fn to_terminal(html: &str) -> String {
+    render(html, Target::Terminal)
+}
+
+/// The title of a puzzle, read from the `--- Day 1: Trebuchet?! ---` heading of its description in markdown.
+pub fn title(markdown: &str) -> Option<String> {
+    let heading = &markdown[markdown.find("--- Day ")?..];
+    let heading = &heading[..heading.find(" ---")?];
+    let (_, title) = heading.split_once(": ")?;
+    Some(title.trim().to_string())
+}
+
+fn render(html: &str, target: Target) -> String {
+    let mut blocks = vec![];
+    render_blocks(&parse(html), target, &mut blocks);
+    blocks.join("\n\n") + "\n"
+}
+
+pub(crate) fn parse(html: &str) -> Vec<Node> {
+    // the bottom of the stack collects the top-level nodes.
+    let mut stack: Vec<OpenElement> = vec![(String::new(), vec![], vec![])];
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+        } else if let Some(tag) = rest.strip_prefix("</") {
+            let end = tag.find('>').unwrap_or(tag.len());
+            let name = tag[..end].trim().to_lowercase();
+            rest = tag.get(end + 1..).unwrap_or("");
+
+            // stray closing tags are ignored, unclosed elements are closed implicitly.
+            if stack.iter().skip(1).any(|(n, ..)| *n == name) {
+                while let Some((n, attrs, children)) = stack.pop() {
+                    let is_match = n == name;
+                    push_element(&mut stack, n, attrs, children);
+                    if is_match {
+                        break;
+                    }
+                }
+            }
+        } else if rest.starts_with('<') && rest[1..].starts_with(|c: char| c.is_ascii_alphabetic())
+        {
+            let end = find_tag_end(rest);
+            let tag = &rest[1..end];
+            rest = rest.get(end + 1..).unwrap_or("");
+
+            let is_self_closing = tag.ends_with('/');
+            let (name, attrs) = parse_tag(tag.trim_end_matches('/'));
+
+            if is_self_closing || VOID_ELEMENTS.contains(&name.as_str()) {
+                push_element(&mut stack, name, attrs, vec![]);
+            } else {
+                stack.push((name, attrs, vec![]));
+            }
+        } else {
+            // the text starts with at least one char, which may be `<` or a multi-byte char.
+            let start = rest.chars().next().map_or(0, char::len_utf8);
+            let end = rest[start..].find('<').map_or(rest.len(), |i| i + start);
+            let text = decode_entities(&rest[..end]);
+            stack.last_mut().unwrap().2.push(Node::Text(text));
+            rest = &rest[end..];
+        }
+    }
+
+    while stack.len() > 1 {
+        let (name, attrs, children) = stack.pop().unwrap();
+        push_element(&mut stack, name, attrs, children);
+    }
+
+    stack.pop().unwrap().2
+}
+
+fn push_element(
+    stack: &mut [OpenElement],
+    name: String,
+    attrs: Vec<(String, String)>,
+    children: Vec<Node>,
+) {
+    let node = Node::Element {
+        name,
+        attrs,
+        children,
+    };
+    stack.last_mut().unwrap().2.push(node);
+}
+
+/// Index of the `>` that ends the tag at the start of `s`, skipping quoted attribute values.
+fn find_tag_end(s: &str) -> usize {
+    let mut quote = None;
+
+    for (i, c) in s.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if q == c => quote = None,
+            (None, '>') => return i,
+            _ => {}
+        }
+    }
+
+    s.len()
+}
+
+fn parse_tag(tag: &str) -> (String, Vec<(String, String)>) {
+    let tag = tag.trim();
+    let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+    let name = tag[..name_end].to_lowercase();
+
+    let mut attrs = vec![];
+    let mut rest = tag[name_end..].trim_start();
+
+    while !rest.is_empty() {
+        let key_end = rest
+            .find(|c: char| c == '=' || c.is_whitespace())
+            .unwrap_or(rest.len());
+        let key = rest[..key_end].to_lowercase();
+        rest = rest[key_end..].trim_start();
+
+        let value = if let Some(value) = rest.strip_prefix('=') {
+            let value = value.trim_start();
+            let (value, remainder) = match value.chars().next() {
+                Some(q @ ('"' | '\'')) => {
+                    let end = value[1..].find(q).map_or(value.len(), |i| i + 1);
+                    (&value[1..end], value.get(end + 1..).unwrap_or(""))
+                }
+                _ => {
+                    let end = value.find(char::is_whitespace).unwrap_or(value.len());
+                    (&value[..end], &value[end..])
+                }
+            };
+            rest = remainder.trim_start();
+            decode_entities(value)
+        } else {
+            String::new()
+        };
+
+        if !key.is_empty() {
+            attrs.push((key, value));
+        }
+    }
+
+    (name, attrs)
+}
+
+fn decode_entities(s: &str) -> String {
+    let mut decoded = String::with_capacity(s.len());
+    let mut rest = s;
+
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let entity = rest[1..]
+            .find(';')
+            .filter(|&end| end <= 8)
+            .and_then(|end| Some((decode_entity(&rest[1..=end])?, end + 2)));
+
+        match entity {
+            Some((c, len)) => {
+                decoded.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    decoded.push_str(rest);
+    decoded
+}
+
+fn decode_entity(entity: &str) -> Option<char> {
+    match entity {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some(' '),
+        _ => {
+            let code = entity.strip_prefix('#')?;
+            let code = match code.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => code.parse().ok()?,
+            };
+            char::from_u32(code)
+        }
+    }
+}
+
+fn is_block(node: &Node) -> bool {
+    matches!(node, Node::Element { name, .. } if matches!(
+        name.as_str(),
+        "article" | "main" | "div" | "body" | "html" | "section" | "p" | "pre" | "ul" | "ol"
+            | "h1" | "h2" | "h3" | "h4" | "h5" | "h6"
+    ))
+}
+
+fn render_blocks(nodes: &[Node], target: Target, blocks: &mut Vec<String>) {
+    let mut inline: Vec<&Node> = vec![];
+
+    for node in nodes {
+        if is_block(node) {
+            push_paragraph(&inline, target, blocks);
+            inline.clear();
+            render_block(node, target, blocks);
+        } else {
+            inline.push(node);
+        }
+    }
+
+    push_paragraph(&inline, target, blocks);
+}
+
+fn push_paragraph(nodes: &[&Node], target: Target, blocks: &mut Vec<String>) {
+    let mut out = String::new();
+    for node in nodes {
+        render_inline(node, target, &mut out);
+    }
+
+    let paragraph = out.trim();
+    if !paragraph.is_empty() {
+        blocks.push(paragraph.to_string());
+    }
+}
+
+fn render_block(node: &Node, target: Target, blocks: &mut Vec<String>) {
+    let Node::Element { name, children, .. } = node else {
+        return;
+    };
+
+    match name.as_str() {
+        "p" => {
+            let children: Vec<&Node> = children.iter().collect();
+            push_paragraph(&children, target, blocks);
+        }
+        "pre" => {
+            let text = node.text();
+            let text = text.trim_end_matches('\n');
+            blocks.push(match target {
+                Target::Markdown => format!("```\n{text}\n```"),
+                Target::Terminal => text
+                    .lines()
+                    .map(|line| format!("    {line}"))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            });
+        }
+        "ul" | "ol" => blocks.push(render_list(node, target, 0)),
+        heading if heading.len() == 2 && heading.starts_with('h') => {
+            let level = heading[1..].parse().unwrap_or(1);
+            let mut out = String::new();
+            for child in children {
+                render_inline(child, target, &mut out);
+            }
+
+            blocks.push(match target {
+                Target::Markdown => format!("{} {}", "#".repeat(level), out.trim()),
+                Target::Terminal => format!("{ANSI_BOLD}{}{ANSI_RESET}", out.trim()),
+            });
+        }
+        _ => render_blocks(children, target, blocks),
+    }
+}
+
+fn render_list(node: &Node, target: Target, depth: usize) -> String {
+    let Node::Element { name, children, .. } = node else {
+        return String::new();
+    };
+
+    let indent = "  ".repeat(depth);
+    let mut lines = vec![];
+
+    let items = children
+        .iter()
+        .filter(|child| matches!(child, Node::Element { name, .. } if name == "li"));
+
+    for (i, item) in items.enumerate() {
+        let Node::Element { children, .. } = item else {
+            continue;
+        };
+
+        let bullet = match (name.as_str(), target) {
+            ("ol", _) => format!("{}.", i + 1),
+            (_, Target::Markdown) => "-".to_string(),
+            (_, Target::Terminal) => "•".to_string(),
+        };
+
+        let mut out = String::new();
+        let mut nested = vec![];
+
+        for child in children {
+            match child {
+                Node::Element { name, .. } if name == "ul" || name == "ol" => {
+                    nested.push(render_list(child, target, depth + 1));
+                }
+                _ => render_inline(child, target, &mut out),
+            }
+        }
+
+        lines.push(format!("{indent}{bullet} {}", out.trim()));
+        lines.extend(nested);
+    }
+
+    lines.join("\n")
+}
+
+fn render_inline(node: &Node, target: Target, out: &mut String) {
+    let (name, children) = match node {
+        Node::Text(text) => {
+            push_text(out, text, target == Target::Markdown);
+            return;
+        }
+        Node::Element { name, children, .. } => (name.as_str(), children),
+    };
+
+    match name {
+        "em" | "strong" | "b" => {
+            let (open, close) = match target {
+                Target::Markdown => ("*", "*"),
+                Target::Terminal => (ANSI_BOLD, ANSI_RESET),
+            };
+            wrap_inline(children, target, out, open, close);
+        }
+        "code" => {
+            let text = node.text();
+            let code = match target {
+                Target::Markdown => {
+                    let fence = if text.contains('`') { "``" } else { "`" };
+                    format!("{fence}{text}{fence}")
+                }
+                Target::Terminal => text,
+            };
+
+            // emphasis inside code can't be expressed in markdown, so the whole code span is emphasized instead.
+            let code = match (node.contains("em"), target) {
+                (true, Target::Markdown) => format!("*{code}*"),
+                (true, Target::Terminal) => format!("{ANSI_BOLD}{code}{ANSI_RESET}"),
+                (false, _) => code,
+            };
+            out.push_str(&code);
+        }
+        "a" => match (node.attr("href"), target) {
+            (Some(href), Target::Markdown) => {
+                let mut text = String::new();
+                for child in children {
+                    render_inline(child, target, &mut text);
+                }
+                out.push_str(&format!("[{}]({href})", text.trim()));
+            }
+            _ => {
+                for child in children {
+                    render_inline(child, target, out);
+                }
+            }
+        },
+        "br" => out.push('\n'),
+        _ => {
+            for child in children {
+                render_inline(child, target, out);
+            }
+        }
+    }
+}
+
+/// Wraps the rendered children in `open` and `close`, keeping surrounding whitespace outside of the markers.
+fn wrap_inline(children: &[Node], target: Target, out: &mut String, open: &str, close: &str) {
+    let mut inner = String::new();
+    for child in children {
+        render_inline(child, target, &mut inner);
+    }
+
+    let trimmed = inner.trim();
+    if trimmed.is_empty() {
+        out.push_str(&inner);
+        return;
+    }
+
+    if inner.starts_with(' ') && !out.ends_with(' ') {
+        out.push(' ');
+    }
+    out.push_str(open);
+    out.push_str(trimmed);
+    out.push_str(close);
+    if inner.ends_with(' ') {
+        out.push(' ');
+    }
+}
+
+/// Appends text with whitespace collapsed to single spaces.
+fn push_text(out: &mut String, text: &str, escape: bool) {
+    for c in text.chars() {
+        if c.is_whitespace() {
+            if !out.is_empty() && !out.ends_with([' ', '\n']) {
+                out.push(' ');
+            }
+        } else {
+            if escape && matches!(c, '\\' | '*' | '_' | '`') {
+                out.push('\\');
+            }
+            out.push(c);
+        }
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{parse, title, to_markdown, to_terminal, Node};
+    use crate::template::{ANSI_BOLD, ANSI_RESET};
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse("<p class=\"x\">a &lt; <em>b</em><br/></p>"),
+            vec![Node::Element {
+                name: "p".into(),
+                attrs: vec![("class".into(), "x".into())],
+                children: vec![
+                    Node::Text("a < ".into()),
+                    Node::Element {
+                        name: "em".into(),
+                        attrs: vec![],
+                        children: vec![Node::Text("b".into())],
+                    },
+                    Node::Element {
+                        name: "br".into(),
+                        attrs: vec![],
+                        children: vec![],
+                    },
+                ],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_markdown_fixture() {
+        assert_eq!(
+            to_markdown(include_str!("fixtures/puzzle.html")),
+            include_str!("fixtures/puzzle.md")
+        );
+    }
+
+    #[test]
+    fn test_non_ascii() {
+        assert_eq!(to_markdown("<p>é</p>"), "é\n");
+        assert_eq!(to_markdown("<p><em>x</em>— y</p>"), "*x*— y\n");
+        assert_eq!(to_terminal("—<br>…"), "—\n…\n");
+    }
+
+    #[test]
+    fn test_title() {
+        assert_eq!(
+            title(include_str!("fixtures/puzzle.md")).as_deref(),
+            Some("Sample Puzzle")
+        );
+        assert_eq!(title("## --- Part Two ---"), None);
+    }
+
+    #[test]
+    fn test_terminal() {
+        assert_eq!(
+            to_terminal(
+                "<article><h2>--- Day 1 ---</h2><p>The answer is <code><em>42</em></code>.</p><pre><code>1 2\n3 4\n</code></pre><ul><li>one</li></ul></article>"
+            ),
+            format!("{ANSI_BOLD}--- Day 1 ---{ANSI_RESET}\n\nThe answer is {ANSI_BOLD}42{ANSI_RESET}.\n\n    1 2\n    3 4\n\n• one\n")
+        );
+    }
+}
@@ -0,0 +1,427 @@
+//! Module that generates sections of the readme, each delimited by a pair of markers like `<!--- stars --->`.
+//! A section is only written if the readme contains its markers, so sections are added by pasting their markers.
+use std::{fmt::Display, fs, io, ops::Range, path::Path};
+
+use crate::template::{
+    answers, aoc_client::get_puzzle_path, bin_name, puzzle, readme_benchmarks::get_path_for_bin,
+    year,
+};
+use crate::{Day, Days};
+
+#[derive(Debug)]
+pub enum Error {
+    Parser(String),
+    IO(io::Error),
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::IO(e)
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Parser(e) => write!(f, "{e}"),
+            Error::IO(e) => write!(f, "could not access the readme: {e}"),
+        }
+    }
+}
+
+/// A generated part of the readme.
+pub trait Section {
+    /// The name inside the markers, e.g. `stars` for `<!--- stars --->`.
+    fn name(&self) -> &'static str;
+
+    /// The markdown between the markers.
+    fn render(&self) -> String;
+}
+
+pub fn marker(name: &str) -> String {
+    format!("<!--- {name} --->")
+}
+
+/// The range from the start of the first marker of a section to the end of the second one.
+/// Only markers on a line of their own count, so the readme can mention a marker in its text.
+fn locate(readme: &str, name: &str) -> Result<Range<usize>, Error> {
+    let marker = marker(name);
+    let mut offset = 0;
+    let mut matches = vec![];
+
+    for line in readme.split_inclusive('\n') {
+        if line.trim() == marker {
+            let start = offset + line.find(&marker).unwrap_or_default();
+            matches.push(start..start + marker.len());
+        }
+        offset += line.len();
+    }
+
+    match matches.as_slice() {
+        [] => Err(Error::Parser(format!("Could not find {marker}."))),
+        [_] => Err(Error::Parser(format!(
+            "{marker}: sections are written between a pair of markers, but only one was found."
+        ))),
+        [start, end] => Ok(start.start..end.end),
+        _ => Err(Error::Parser(format!(
+            "{marker}: too many occurences of marker in README."
+        ))),
+    }
+}
+
+/// Replaces the contents between the markers of a section.
+pub fn replace_section(readme: &mut String, section: &dyn Section) -> Result<(), Error> {
+    let range = locate(readme, section.name())?;
+    let marker = marker(section.name());
+    let contents = format!("{marker}\n{}\n{marker}", section.render());
+    readme.replace_range(range, &contents);
+    Ok(())
+}
+
+/// Whether the readme has a marker of the section on a line of its own.
+fn has_marker(readme: &str, name: &str) -> bool {
+    let marker = marker(name);
+    readme.lines().any(|line| line.trim() == marker)
+}
+
+/// Replaces every section whose markers are in the readme and returns the names of those sections.
+pub fn update_sections(
+    readme: &mut String,
+    sections: &[&dyn Section],
+) -> Result<Vec<&'static str>, Error> {
+    let mut updated = vec![];
+
+    for section in sections {
+        if has_marker(readme, section.name()) {
+            replace_section(readme, *section)?;
+            updated.push(section.name());
+        }
+    }
+
+    Ok(updated)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Edit<'a> {
+    Keep(&'a str),
+    Remove(&'a str),
+    Add(&'a str),
+}
+
+/// A line diff of two versions of the readme, e.g. to preview an update.
+/// Every hunk starts with the line numbers it changes, e.g. `@@ -10 +10 @@`, followed by removed (`-`) and added (`+`) lines.
+pub fn diff(old: &str, new: &str) -> Vec<String> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // lengths of the longest common subsequences of the suffixes of both versions.
+    let mut lcs = vec![vec![0_usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut edits = vec![];
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            edits.push(Edit::Keep(old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            edits.push(Edit::Remove(old[i]));
+            i += 1;
+        } else {
+            edits.push(Edit::Add(new[j]));
+            j += 1;
+        }
+    }
+
+    let mut lines = vec![];
+    let (mut old_line, mut new_line) = (1, 1);
+    let mut in_hunk = false;
+
+    for edit in edits {
+        match edit {
+            Edit::Keep(_) => {
+                in_hunk = false;
+                old_line += 1;
+                new_line += 1;
+                continue;
+            }
+            _ if !in_hunk => {
+                lines.push(format!("@@ -{old_line} +{new_line} @@"));
+                in_hunk = true;
+            }
+            _ => {}
+        }
+
+        match edit {
+            Edit::Remove(line) => {
+                lines.push(format!("-{line}"));
+                old_line += 1;
+            }
+            Edit::Add(line) => {
+                lines.push(format!("+{line}"));
+                new_line += 1;
+            }
+            Edit::Keep(_) => unreachable!(),
+        }
+    }
+
+    lines
+}
+
+/// Progress of the year, based on the recorded answers of each part.
+pub struct Stars {
+    pub stars: Vec<(Day, [bool; 2])>,
+}
+
+impl Stars {
+    pub fn collect() -> Self {
+        Self {
+            stars: Days::all()
+                .iter()
+                .map(|day| (day, [1, 2].map(|part| answers::read(day, part).is_some())))
+                .collect(),
+        }
+    }
+}
+
+impl Section for Stars {
+    fn name(&self) -> &'static str {
+        "stars"
+    }
+
+    fn render(&self) -> String {
+        let count = self
+            .stars
+            .iter()
+            .flat_map(|(_, s)| s)
+            .filter(|&&s| s)
+            .count();
+
+        let mut lines = vec![
+            "## Progress".to_string(),
+            String::new(),
+            format!("**{count}/50 ⭐**"),
+        ];
+
+        if count > 0 {
+            lines.push(String::new());
+            lines.push("| Day | Part 1 | Part 2 |".into());
+            lines.push("| :---: | :---: | :---: |".into());
+
+            let star = |is_solved: bool| if is_solved { "⭐" } else { " " };
+            for (day, [part_1, part_2]) in &self.stars {
+                if *part_1 || *part_2 {
+                    lines.push(format!(
+                        "| Day {} | {} | {} |",
+                        day.into_inner(),
+                        star(*part_1),
+                        star(*part_2)
+                    ));
+                }
+            }
+        }
+
+        lines.join("\n")
+    }
+}
+
+/// A day with a downloaded puzzle or a solution.
+pub struct PuzzleEntry {
+    pub day: Day,
+    /// The title if the puzzle was downloaded, see [`puzzle::title`].
+    pub title: Option<String>,
+    pub has_puzzle: bool,
+    pub is_scaffolded: bool,
+}
+
+/// The titles of all puzzles, linked to their descriptions and solutions.
+pub struct Puzzles {
+    pub entries: Vec<PuzzleEntry>,
+}
+
+impl Puzzles {
+    pub fn collect() -> Self {
+        let entries = Days::all()
+            .iter()
+            .map(|day| {
+                let description = fs::read_to_string(get_puzzle_path(day)).ok();
+                PuzzleEntry {
+                    day,
+                    title: description.as_deref().and_then(puzzle::title),
+                    has_puzzle: description.is_some(),
+                    is_scaffolded: is_scaffolded(day),
+                }
+            })
+            .filter(|entry| entry.has_puzzle || entry.is_scaffolded)
+            .collect();
+
+        Self { entries }
+    }
+}
+
+impl Section for Puzzles {
+    fn name(&self) -> &'static str {
+        "puzzles"
+    }
+
+    fn render(&self) -> String {
+        let mut lines = vec!["## Puzzles".to_string(), String::new()];
+
+        for entry in &self.entries {
+            let name = match &entry.title {
+                Some(title) => format!("Day {}: {title}", entry.day.into_inner()),
+                None => format!("Day {}", entry.day.into_inner()),
+            };
+
+            let mut line = if entry.has_puzzle {
+                format!("- [{name}](./{})", get_puzzle_path(entry.day))
+            } else {
+                format!("- {name}")
+            };
+
+            if entry.is_scaffolded {
+                line.push_str(&format!(" ([solution]({}))", get_path_for_bin(entry.day)));
+            }
+
+            lines.push(line);
+        }
+
+        lines.join("\n")
+    }
+}
+
+/// The size of every solution, counting lines of code outside of tests.
+pub struct LinesOfCode {
+    pub lines: Vec<(Day, usize)>,
+}
+
+impl LinesOfCode {
+    pub fn collect() -> Self {
+        let lines = Days::all()
+            .iter()
+            .filter_map(|day| {
+                let source = fs::read_to_string(bin_path(day)).ok()?;
+                Some((day, count_lines(&source)))
+            })
+            .collect();
+
+        Self { lines }
+    }
+}
+
+impl Section for LinesOfCode {
+    fn name(&self) -> &'static str {
+        "lines of code"
+    }
+
+    fn render(&self) -> String {
+        let mut lines = vec![
+            "## Lines of code".to_string(),
+            String::new(),
+            "| Day | Lines |".into(),
+            "| :---: | ---: |".into(),
+        ];
+
+        for (day, count) in &self.lines {
+            lines.push(format!(
+                "| [Day {}]({}) | {count} |",
+                day.into_inner(),
+                get_path_for_bin(*day)
+            ));
+        }
+
+        let total: usize = self.lines.iter().map(|(_, count)| count).sum();
+        lines.push(String::new());
+        lines.push(format!("**Total: {total} lines**"));
+
+        lines.join("\n")
+    }
+}
+
+/// Counts the lines of a solution that aren't blank or comments, up to its test module.
+fn count_lines(source: &str) -> usize {
+    source
+        .lines()
+        .map(str::trim)
+        .take_while(|line| *line != "#[cfg(test)]")
+        .filter(|line| !line.is_empty() && !line.starts_with("//"))
+        .count()
+}
+
+fn bin_path(day: Day) -> String {
+    format!("src/bin/{}.rs", bin_name(year(), day))
+}
+
+fn is_scaffolded(day: Day) -> bool {
+    Path::new(&bin_path(day)).exists()
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{count_lines, diff, update_sections, LinesOfCode, Section, Stars};
+    use crate::day;
+
+    #[test]
+    fn test_update_sections() {
+        let stars = Stars {
+            stars: vec![(day!(1), [true, true]), (day!(2), [true, false])],
+        };
+        let lines = LinesOfCode { lines: vec![] };
+
+        let mut readme = "# AoC\n<!--- stars --->\nold\n<!--- stars --->\nend".to_string();
+        let updated = update_sections(&mut readme, &[&stars, &lines]).unwrap();
+
+        assert_eq!(updated, ["stars"]);
+        assert_eq!(
+            readme,
+            "# AoC\n<!--- stars --->\n## Progress\n\n**3/50 ⭐**\n\n| Day | Part 1 | Part 2 |\n| :---: | :---: | :---: |\n| Day 1 | ⭐ | ⭐ |\n| Day 2 | ⭐ |   |\n<!--- stars --->\nend"
+        );
+
+        // a single marker is an error instead of a section.
+        let mut readme = "<!--- stars --->".to_string();
+        assert!(update_sections(&mut readme, &[&stars]).is_err());
+        assert_eq!(stars.name(), "stars");
+    }
+
+    #[test]
+    fn test_update_sections_skips_mentions() {
+        let stars = Stars {
+            stars: vec![(day!(1), [true, false])],
+        };
+        let lines = LinesOfCode { lines: vec![] };
+
+        let docs = "| `<!--- stars --->` | The stars. |\n| `<!--- lines of code --->` | The lines. |\nPaste <!--- stars ---> on its own line.";
+        let mut readme =
+            format!("# AoC\n<!--- stars --->\nold\n  <!--- stars --->\n\n## Docs\n{docs}\n");
+        let updated = update_sections(&mut readme, &[&stars, &lines]).unwrap();
+
+        assert_eq!(updated, ["stars"]);
+        assert!(readme.starts_with("# AoC\n<!--- stars --->\n## Progress\n"));
+        assert!(!readme.contains("old"));
+        assert!(readme.ends_with(&format!("**1/50 ⭐**\n\n| Day | Part 1 | Part 2 |\n| :---: | :---: | :---: |\n| Day 1 | ⭐ |   |\n<!--- stars --->\n\n## Docs\n{docs}\n")));
+    }
+
+    #[test]
+    fn test_count_lines() {
+        let source = "use std::io;\n\n// a comment\nfn main() {\n    /// docs\n}\n\n#[cfg(test)]\nmod tests {\n}\n";
+        assert_eq!(count_lines(source), 3);
+    }
+
+    #[test]
+    fn test_diff() {
+        assert!(diff("a\nb\nc", "a\nb\nc").is_empty());
+        assert_eq!(
+            diff("a\nb\nc\nd", "a\nx\nc\nd\ne"),
+            ["@@ -2 +2 @@", "-b", "+x", "@@ -5 +5 @@", "+e"]
+        );
+    }
+}
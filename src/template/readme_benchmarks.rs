@@ -1,97 +1,111 @@
-/// Module that updates the readme me with timing information.
-/// The approach taken is similar to how `aoc-readme-stars` handles this.
-use std::{fs, io};
+//! Module that updates the readme me with timing information.
+//! The approach taken is similar to how `aoc-readme-stars` handles this.
+use std::{collections::BTreeMap, fs, time::Duration};
 
+use crate::template::baseline::{Baseline, PARSE_PART};
+use crate::template::readme::{replace_section, Section};
+use crate::template::{bin_name, year};
 use crate::Day;
 
-static MARKER: &str = "<!--- benchmarking table --->";
+pub use crate::template::readme::Error;
 
-#[derive(Debug)]
-pub enum Error {
-    Parser(String),
-    IO(io::Error),
-}
-
-impl From<std::io::Error> for Error {
-    fn from(e: std::io::Error) -> Self {
-        Error::IO(e)
-    }
-}
+const NAME: &str = "benchmarking table";
 
 #[derive(Clone)]
 pub struct Timings {
     pub day: Day,
+    pub parse: Option<String>,
     pub part_1: Option<String>,
     pub part_2: Option<String>,
     pub total_nanos: f64,
 }
 
-pub struct TablePosition {
-    pos_start: usize,
-    pos_end: usize,
-}
-
 #[must_use]
 pub fn get_path_for_bin(day: Day) -> String {
-    format!("./src/bin/{day}.rs")
+    format!("./src/bin/{}.rs", bin_name(year(), day))
 }
 
-fn locate_table(readme: &str) -> Result<TablePosition, Error> {
-    let matches: Vec<_> = readme.match_indices(MARKER).collect();
+/// The benchmark table, written between `<!--- benchmarking table --->` markers.
+pub struct BenchmarkTable {
+    pub timings: Vec<Timings>,
+    pub total_millis: f64,
+}
 
-    if matches.len() > 2 {
-        return Err(Error::Parser(
-            "{}: too many occurences of marker in README.".into(),
-        ));
+impl BenchmarkTable {
+    /// Builds the table from stored benchmarks, e.g. to regenerate the readme without running the solutions.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn from_baseline(baseline: &Baseline) -> Self {
+        let mut days: BTreeMap<Day, Timings> = BTreeMap::new();
+
+        for timing in &baseline.timings {
+            let timings = days.entry(timing.day).or_insert_with(|| Timings {
+                day: timing.day,
+                parse: None,
+                part_1: None,
+                part_2: None,
+                total_nanos: 0_f64,
+            });
+
+            let duration = Some(format!("{:.1?}", timing.duration));
+            match timing.part {
+                PARSE_PART => timings.parse = duration,
+                1 => timings.part_1 = duration,
+                _ => timings.part_2 = duration,
+            }
+            timings.total_nanos += timing.duration.as_nanos() as f64;
+        }
+
+        let total_nanos: f64 = days.values().map(|t| t.total_nanos).sum();
+
+        Self {
+            timings: days.into_values().collect(),
+            total_millis: total_nanos / 1_000_000_f64,
+        }
     }
-
-    let pos_start = matches
-        .first()
-        .map(|m| m.0)
-        .ok_or_else(|| Error::Parser("Could not find table start position.".into()))?;
-
-    let pos_end = matches
-        .last()
-        .map(|m| m.0 + m.1.len())
-        .ok_or_else(|| Error::Parser("Could not find table end position.".into()))?;
-
-    Ok(TablePosition { pos_start, pos_end })
 }
 
-fn construct_table(prefix: &str, timings: Vec<Timings>, total_millis: f64) -> String {
-    let header = format!("{prefix} Benchmarks");
-
-    let mut lines: Vec<String> = vec![
-        MARKER.into(),
-        header,
-        String::new(),
-        "| Day | Part 1 | Part 2 |".into(),
-        "| :---: | :---: | :---:  |".into(),
-    ];
-
-    for timing in timings {
-        let path = get_path_for_bin(timing.day);
-        lines.push(format!(
-            "| [Day {}]({}) | `{}` | `{}` |",
-            timing.day.into_inner(),
-            path,
-            timing.part_1.unwrap_or_else(|| "-".into()),
-            timing.part_2.unwrap_or_else(|| "-".into())
-        ));
+impl Section for BenchmarkTable {
+    fn name(&self) -> &'static str {
+        NAME
     }
 
-    lines.push(String::new());
-    lines.push(format!("**Total: {total_millis:.2}ms**"));
-    lines.push(MARKER.into());
-
-    lines.join("\n")
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn render(&self) -> String {
+        let mut lines: Vec<String> = vec![
+            "## Benchmarks".into(),
+            String::new(),
+            "| Day | Parse | Part 1 | Part 2 | Total |".into(),
+            "| :---: | :---: | :---: | :---: | :---: |".into(),
+        ];
+
+        for timing in &self.timings {
+            let path = get_path_for_bin(timing.day);
+            lines.push(format!(
+                "| [Day {}]({}) | `{}` | `{}` | `{}` | `{:.1?}` |",
+                timing.day.into_inner(),
+                path,
+                timing.parse.as_deref().unwrap_or("-"),
+                timing.part_1.as_deref().unwrap_or("-"),
+                timing.part_2.as_deref().unwrap_or("-"),
+                Duration::from_nanos(timing.total_nanos as u64)
+            ));
+        }
+
+        lines.push(String::new());
+        lines.push(format!("**Total: {:.2}ms**", self.total_millis));
+
+        lines.join("\n")
+    }
 }
 
 fn update_content(s: &mut String, timings: Vec<Timings>, total_millis: f64) -> Result<(), Error> {
-    let positions = locate_table(s)?;
-    let table = construct_table("##", timings, total_millis);
-    s.replace_range(positions.pos_start..positions.pos_end, &table);
-    Ok(())
+    replace_section(
+        s,
+        &BenchmarkTable {
+            timings,
+            total_millis,
+        },
+    )
 }
 
 pub fn update(timings: Vec<Timings>, total_millis: f64) -> Result<(), Error> {
@@ -104,25 +118,33 @@ pub fn update(timings: Vec<Timings>, total_millis: f64) -> Result<(), Error> {
 
 #[cfg(feature = "test_lib")]
 mod tests {
-    use super::{update_content, Timings, MARKER};
+    use super::{update_content, BenchmarkTable, Timings};
     use crate::day;
+    use crate::template::baseline::{Baseline, PartTiming};
+    use crate::template::readme::Section;
+    use std::time::Duration;
+
+    const MARKER: &str = "<!--- benchmarking table --->";
 
     fn get_mock_timings() -> Vec<Timings> {
         vec![
             Timings {
                 day: day!(1),
+                parse: None,
                 part_1: Some("10ms".into()),
                 part_2: Some("20ms".into()),
                 total_nanos: 3e+10,
             },
             Timings {
                 day: day!(2),
+                parse: Some("5ms".into()),
                 part_1: Some("30ms".into()),
                 part_2: Some("40ms".into()),
                 total_nanos: 7e+10,
             },
             Timings {
                 day: day!(4),
+                parse: None,
                 part_1: Some("40ms".into()),
                 part_2: Some("50ms".into()),
                 total_nanos: 9e+10,
@@ -140,20 +162,20 @@ mod tests {
     #[test]
     #[should_panic]
     fn errors_if_too_many_markers_present() {
-        let mut s = format!("{} {} {}", MARKER, MARKER, MARKER);
+        let mut s = format!("{}\n{}\n{}", MARKER, MARKER, MARKER);
         update_content(&mut s, get_mock_timings(), 190.0).unwrap();
     }
 
     #[test]
     fn updates_empty_benchmarks() {
-        let mut s = format!("foo\nbar\n{}{}\nbaz", MARKER, MARKER);
+        let mut s = format!("foo\nbar\n{}\n{}\nbaz", MARKER, MARKER);
         update_content(&mut s, get_mock_timings(), 190.0).unwrap();
         assert_eq!(s.contains("## Benchmarks"), true);
     }
 
     #[test]
     fn updates_existing_benchmarks() {
-        let mut s = format!("foo\nbar\n{}{}\nbaz", MARKER, MARKER);
+        let mut s = format!("foo\nbar\n{}\n{}\nbaz", MARKER, MARKER);
         update_content(&mut s, get_mock_timings(), 190.0).unwrap();
         update_content(&mut s, get_mock_timings(), 190.0).unwrap();
         assert_eq!(s.matches(MARKER).collect::<Vec<&str>>().len(), 2);
@@ -170,11 +192,11 @@ mod tests {
             "<!--- benchmarking table --->",
             "## Benchmarks",
             "",
-            "| Day | Part 1 | Part 2 |",
-            "| :---: | :---: | :---:  |",
-            "| [Day 1](./src/bin/01.rs) | `10ms` | `20ms` |",
-            "| [Day 2](./src/bin/02.rs) | `30ms` | `40ms` |",
-            "| [Day 4](./src/bin/04.rs) | `40ms` | `50ms` |",
+            "| Day | Parse | Part 1 | Part 2 | Total |",
+            "| :---: | :---: | :---: | :---: | :---: |",
+            "| [Day 1](./src/bin/2023-01.rs) | `-` | `10ms` | `20ms` | `30.0s` |",
+            "| [Day 2](./src/bin/2023-02.rs) | `5ms` | `30ms` | `40ms` | `70.0s` |",
+            "| [Day 4](./src/bin/2023-04.rs) | `-` | `40ms` | `50ms` | `90.0s` |",
             "",
             "**Total: 190.00ms**",
             "<!--- benchmarking table --->",
@@ -183,4 +205,31 @@ mod tests {
         .join("\n");
         assert_eq!(s, expected);
     }
+
+    #[test]
+    fn table_from_baseline() {
+        let timing = |day, part, micros| PartTiming {
+            day,
+            part,
+            duration: Duration::from_micros(micros),
+        };
+        let baseline = Baseline {
+            commit: "1a2b3c4".into(),
+            timestamp: 0,
+            timings: vec![
+                timing(day!(2), 1, 300),
+                timing(day!(1), 0, 100),
+                timing(day!(1), 1, 200),
+            ],
+        };
+
+        let table = BenchmarkTable::from_baseline(&baseline);
+        assert_eq!(table.total_millis, 0.6);
+        assert!(table.render().contains(
+            "| [Day 1](./src/bin/2023-01.rs) | `100.0µs` | `200.0µs` | `-` | `300.0µs` |"
+        ));
+        assert!(table
+            .render()
+            .contains("| [Day 2](./src/bin/2023-02.rs) | `-` | `300.0µs` | `-` | `300.0µs` |"));
+    }
 }
@@ -0,0 +1,39 @@
+//! In-process registry of solutions.
+//! Every `src/bin/YYYY-NN.rs` is compiled into the main binary as well, which lets `cargo all` run all days without spawning a child process per day.
+use crate::template::runner::{DayResult, Failure, RunOptions};
+use crate::{Day, Year};
+
+/// A type-erased solution for a single day.
+/// Implementations are generated by the [`solution!`](crate::solution) macro.
+pub trait Solution: Sync {
+    /// The day this solution belongs to.
+    fn day(&self) -> Day;
+
+    /// Runs the solution against `input`, reporting its output as `cargo solve` does.
+    fn run(&self, input: &str, options: &RunOptions) -> DayResult;
+
+    /// Solves a part without timing or reporting it, [`None`] if the part is not solved.
+    /// Panics are not caught, an error returned by the part is.
+    fn answer(&self, part: u8, input: &str) -> Result<Option<String>, Failure>;
+}
+
+/// A table of solutions, indexed by year and day.
+/// The year is taken from the name of the binary, so solutions don't need to know it.
+#[derive(Clone, Copy)]
+pub struct Registry {
+    solutions: &'static [(Year, &'static dyn Solution)],
+}
+
+impl Registry {
+    pub const fn new(solutions: &'static [(Year, &'static dyn Solution)]) -> Self {
+        Self { solutions }
+    }
+
+    /// Returns the solution for `day` of `year`, if that day has been scaffolded.
+    pub fn get(&self, year: Year, day: Day) -> Option<&'static dyn Solution> {
+        self.solutions
+            .iter()
+            .find(|(y, s)| *y == year && s.day() == day)
+            .map(|(_, s)| *s)
+    }
+}
@@ -1,63 +1,950 @@
-/// Encapsulates code that interacts with solution functions.
-use crate::template::{aoc_cli, ANSI_ITALIC, ANSI_RESET};
+//! Encapsulates code that interacts with solution functions.
+use crate::template::answers::{self, Verdict};
+use crate::template::aoc_client::{self, AocClient, AocClientError};
+use crate::template::benchmark::{BenchConfig, BenchStats};
+use crate::template::limits::{self, Limits, Stage};
+use crate::template::params::{Override, Params};
+use crate::template::submissions::{self, Submission, SubmissionOutcome};
+use crate::template::{ANSI_ITALIC, ANSI_RESET};
 use crate::Day;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::error::Error;
 use std::fmt::Display;
+use std::fs::File;
 use std::io::{stdout, Write};
-use std::process::Output;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Mutex, Once, OnceLock};
 use std::time::{Duration, Instant};
-use std::{cmp, env, process};
+use std::{env, process};
+use tinyjson::JsonValue;
 
 use super::ANSI_BOLD;
 
-pub fn run_part<I: Clone, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
+/// Output format of the runner.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Format {
+    /// Human-readable output, e.g. `Part 1: 42 (166.0ns)`.
+    #[default]
+    Human,
+    /// One JSON record per part, see [`PartResult::to_json`], written to the file passed with `--output`.
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = FormatFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "human" => Ok(Self::Human),
+            "json" => Ok(Self::Json),
+            _ => Err(FormatFromStrError),
+        }
+    }
+}
+
+impl Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Human => f.write_str("human"),
+            Self::Json => f.write_str("json"),
+        }
+    }
+}
+
+/// An error which can be returned when parsing a [`Format`].
+#[derive(Debug)]
+pub struct FormatFromStrError;
+
+impl Error for FormatFromStrError {}
+
+impl Display for FormatFromStrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("expecting one of `human` or `json`")
+    }
+}
+
+/// Options that control how solution parts are executed and reported.
+#[derive(Debug, Clone, Default)]
+pub struct RunOptions {
+    pub is_timed: bool,
+    pub bench: BenchConfig,
+    /// Compare answers with the ones recorded in `data/answers`.
+    pub verify: bool,
+    pub format: Format,
+    /// Overrides of the solution's parameters, see [`crate::param!`].
+    pub params: Params,
+    /// Limits on the time and memory a single part may use, see [`limits::run`].
+    pub limits: Limits,
+    /// Don't print anything while running, e.g. because the caller prints the results once several days completed.
+    pub is_quiet: bool,
+    /// Only run this part, the other one is skipped. [`None`] runs both parts.
+    pub part: Option<u8>,
+}
+
+impl RunOptions {
+    /// Reads the options from the arguments passed to a solution binary, e.g. `cargo solve 1 --time --format json`.
+    pub fn from_args() -> Self {
+        Self::parse(&env::args().collect::<Vec<_>>())
+    }
+
+    pub(crate) fn parse(args: &[String]) -> Self {
+        let defaults = BenchConfig::default();
+
+        Self {
+            is_timed: args.iter().any(|x| x == "--time"),
+            bench: BenchConfig {
+                warmup_time: arg_value(args, "--warmup-time")
+                    .map_or(defaults.warmup_time, Duration::from_millis),
+                target_time: arg_value(args, "--bench-time")
+                    .map_or(defaults.target_time, Duration::from_millis),
+                iterations: arg_value(args, "--bench-iterations"),
+            },
+            verify: args.iter().any(|x| x == "--verify"),
+            format: output_format(args),
+            params: param_overrides(args),
+            limits: Limits {
+                timeout: arg_value(args, "--timeout").map(Duration::from_millis),
+                memory_mb: arg_value(args, "--memory-limit"),
+            },
+            is_quiet: false,
+            part: None,
+        }
+    }
+}
+
+/// Reads `--format` and sends the records to `--output`. Exits if `--format json` is passed without `--output`,
+/// as the records can't share stdout with whatever the solution prints itself.
+fn output_format(args: &[String]) -> Format {
+    let format = arg_value(args, "--format").unwrap_or_default();
+
+    match arg_value::<PathBuf>(args, "--output") {
+        Some(path) => set_output(&path),
+        None if format == Format::Json => {
+            eprintln!("{MISSING_OUTPUT}");
+            process::exit(1);
+        }
+        None => {}
+    }
+
+    format
+}
+
+pub const MISSING_OUTPUT: &str =
+    "`--format json` requires `--output <path>` to write the records to.";
+
+/// The file that JSON records are written to, see [`set_output`].
+static RECORDS: OnceLock<Mutex<File>> = OnceLock::new();
+
+/// Writes the JSON records to `path` instead of stdout. Only the first call creates the file, later calls are ignored.
+/// Exits if the file can't be created.
+pub fn set_output(path: &Path) {
+    if RECORDS.get().is_some() {
+        return;
+    }
+
+    match File::create(path) {
+        Ok(file) => {
+            let _ = RECORDS.set(Mutex::new(file));
+        }
+        Err(e) => {
+            eprintln!("Failed to create {}: {e}", path.display());
+            process::exit(1);
+        }
+    }
+}
+
+/// Writes a JSON record to the file set with [`set_output`], or to stdout if there is none. Exits if the record can't be written.
+pub(crate) fn write_record(record: &str) {
+    let written = match RECORDS.get() {
+        Some(file) => writeln!(file.lock().unwrap_or_else(|e| e.into_inner()), "{record}"),
+        None => writeln!(stdout(), "{record}"),
+    };
+
+    if let Err(e) = written {
+        eprintln!("Failed to write a record: {e}");
+        process::exit(1);
+    }
+}
+
+/// Collects the values of every `--param name=value`. Exits if a value can't be parsed.
+fn param_overrides(args: &[String]) -> Params {
+    let mut params = Params::default();
+
+    for (i, _) in args.iter().enumerate().filter(|(_, x)| *x == "--param") {
+        match args.get(i + 1).map(|x| x.parse::<Override>()) {
+            Some(Ok(o)) => params.insert(&o.name, &o.value),
+            _ => {
+                eprintln!("Unexpected command-line input. Invalid or missing value for \"--param\", expecting `name=value`.");
+                process::exit(1);
+            }
+        }
+    }
+
+    params
+}
+
+/// Looks up the value following `name` in `args`. Exits if the value is missing or can't be parsed.
+pub(crate) fn arg_value<T: FromStr>(args: &[String], name: &str) -> Option<T> {
+    let index = args.iter().position(|x| x == name)?;
+
+    match args.get(index + 1).map(|x| x.parse()) {
+        Some(Ok(value)) => Some(value),
+        _ => {
+            eprintln!("Unexpected command-line input. Invalid or missing value for \"{name}\".");
+            process::exit(1);
+        }
+    }
+}
+
+/// The return type of a solution part: [`Option<T>`], [`Result<T, E>`] or a plain answer such as [`u64`] or [`String`].
+/// The error of a [`Result`] can be anything that converts into a boxed [`Error`], e.g. `&'static str`, [`String`] or a custom error type.
+/// Its sources are reported as the causes of the failure. An [`Unsolved`] error marks the part as unsolved instead of failed.
+pub trait PartOutput {
+    /// The answer in its displayed form, without consuming the output. [`None`] if the part is unsolved or failed.
+    fn answer(&self) -> Option<String>;
+
+    /// The answer in its displayed form, [`None`] if the part is unsolved.
+    fn into_answer(self) -> Result<Option<String>, Failure>;
+}
+
+impl<T: Display> PartOutput for Option<T> {
+    fn answer(&self) -> Option<String> {
+        self.as_ref().map(ToString::to_string)
+    }
+
+    fn into_answer(self) -> Result<Option<String>, Failure> {
+        Ok(self.answer())
+    }
+}
+
+impl<T: Display, E: Into<Box<dyn Error>>> PartOutput for Result<T, E> {
+    fn answer(&self) -> Option<String> {
+        self.as_ref().ok().map(ToString::to_string)
+    }
+
+    fn into_answer(self) -> Result<Option<String>, Failure> {
+        match self {
+            Ok(answer) => Ok(Some(answer.to_string())),
+            Err(e) => {
+                let e: Box<dyn Error> = e.into();
+                if e.is::<Unsolved>() {
+                    return Ok(None);
+                }
+
+                let mut chain = vec![e.to_string()];
+                let mut source = e.source();
+                while let Some(cause) = source {
+                    chain.push(cause.to_string());
+                    source = cause.source();
+                }
+                Err(Failure::Error(chain))
+            }
+        }
+    }
+}
+
+/// The error of a part that returns a [`Result`] but isn't solved yet, which is reported like a part returning [`None`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Unsolved;
+
+impl Display for Unsolved {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("not solved yet")
+    }
+}
+
+impl Error for Unsolved {}
+
+macro_rules! impl_plain_output {
+    ($($t:ty),*) => {
+        $(impl PartOutput for $t {
+            fn answer(&self) -> Option<String> {
+                Some(self.to_string())
+            }
+
+            fn into_answer(self) -> Result<Option<String>, Failure> {
+                Ok(self.answer())
+            }
+        })*
+    };
+}
+
+impl_plain_output!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, String, &str, char
+);
+
+/// Why a part or parsing didn't run to completion.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Failure {
+    /// The message and location of a panic.
+    Panicked(String),
+    /// The error returned by the part, followed by its causes.
+    Error(Vec<String>),
+    /// A single run took longer than the timeout.
+    TimedOut(Duration),
+    /// The resident set size grew by more than the limit, in megabytes.
+    OutOfMemory(u64),
+}
+
+impl Failure {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Panicked(_) => "panicked",
+            Self::Error(_) => "error",
+            Self::TimedOut(_) => "timeout",
+            Self::OutOfMemory(_) => "memory",
+        }
+    }
+
+    /// The failure on a single line, causes are separated by `: `.
+    pub fn message(&self) -> String {
+        match self {
+            Self::Panicked(message) => message.clone(),
+            Self::Error(chain) => chain.join(": "),
+            Self::TimedOut(limit) => format!("timed out after {limit:.1?}"),
+            Self::OutOfMemory(limit) => format!("exceeded the memory limit of {limit}MB"),
+        }
+    }
+}
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Panicked(message) => write!(f, "panicked: {message}"),
+            Self::Error(chain) => {
+                write!(f, "error: {}", chain.first().map_or("", String::as_str))?;
+                for cause in chain.iter().skip(1) {
+                    write!(f, "\n  caused by: {cause}")?;
+                }
+                Ok(())
+            }
+            Self::TimedOut(_) | Self::OutOfMemory(_) => f.write_str(&self.message()),
+        }
+    }
+}
+
+/// The outcome of running a single part of a solution.
+#[derive(Debug, Clone)]
+pub struct PartResult {
+    pub day: Day,
+    pub part: u8,
+    /// The answer in its displayed form, [`None`] if the part is not solved.
+    pub answer: Option<String>,
+    /// Execution time of a single run. When benched, this is the median of all samples.
+    pub duration: Duration,
+    pub samples: u128,
+    /// Statistics of the benchmark, [`None`] if the part was not benched.
+    pub stats: Option<BenchStats>,
+    /// Comparison with the recorded answer, [`None`] if the answer was not verified.
+    pub verdict: Option<Verdict>,
+    /// Why the part failed, [`None`] if it ran to completion.
+    pub error: Option<Failure>,
+}
+
+impl PartResult {
+    /// A part that was not run, e.g. because only the other part was selected.
+    pub(crate) fn skipped(day: Day, part: u8) -> Self {
+        Self {
+            day,
+            part,
+            answer: None,
+            duration: Duration::ZERO,
+            samples: 0,
+            stats: None,
+            verdict: None,
+            error: None,
+        }
+    }
+
+    /// A part that could not run at all, e.g. because parsing the input failed.
+    pub fn failed(day: Day, part: u8, error: Failure) -> Self {
+        Self {
+            day,
+            part,
+            answer: None,
+            duration: Duration::ZERO,
+            samples: 0,
+            stats: None,
+            verdict: None,
+            error: Some(error),
+        }
+    }
+
+    pub fn status(&self) -> &'static str {
+        match &self.error {
+            Some(error) => error.as_str(),
+            None if self.answer.is_some() => "solved",
+            None => "unsolved",
+        }
+    }
+
+    /// Serializes the result to a single line of JSON, e.g.
+    /// `{"day":1,"part":1,"answer":"42","duration_nanos":166,"samples":1,"mean_nanos":null,...,"status":"solved","verdict":null,...,"error":null,...}`.
+    /// Benchmark statistics are `null` if the part was not benched, the verdict is `null` if the answer was not verified.
+    /// The record holds the whole result, see [`PartResult::from_record`].
+    pub fn to_json(&self) -> String {
+        let answer = match &self.answer {
+            Some(answer) => JsonValue::String(answer.clone()),
+            None => JsonValue::Null,
+        };
+
+        let mut fields = vec![
+            ("day", JsonValue::Number(f64::from(self.day.into_inner()))),
+            ("part", JsonValue::Number(f64::from(self.part))),
+            ("answer", answer),
+        ];
+        fields.extend(timing_fields(
+            self.duration,
+            self.samples,
+            self.stats.as_ref(),
+        ));
+        fields.push(("status", JsonValue::String(self.status().into())));
+        fields.push((
+            "verdict",
+            self.verdict
+                .as_ref()
+                .map_or(JsonValue::Null, |v| JsonValue::String(v.as_str().into())),
+        ));
+        fields.push((
+            "expected",
+            match &self.verdict {
+                Some(Verdict::Fail { expected }) => JsonValue::String(expected.clone()),
+                _ => JsonValue::Null,
+            },
+        ));
+        fields.extend(failure_fields(self.error.as_ref()));
+
+        json_line(&fields)
+    }
+
+    /// Reads a result written by [`PartResult::to_json`].
+    pub(crate) fn from_record(record: &Record) -> Option<Self> {
+        let verdict = match record.str("verdict") {
+            None => None,
+            Some("pass") => Some(Verdict::Pass),
+            Some("missing") => Some(Verdict::Missing),
+            Some("fail") => Some(Verdict::Fail {
+                expected: record.str("expected")?.to_string(),
+            }),
+            Some(_) => return None,
+        };
+
+        Some(Self {
+            day: record.day()?,
+            part: u8::try_from(record.number("part")?).ok()?,
+            answer: record.str("answer").map(str::to_string),
+            duration: record.duration("duration_nanos")?,
+            samples: record.number("samples")?,
+            stats: record.stats(),
+            verdict,
+            error: record.failure(),
+        })
+    }
+}
+
+/// The outcome of parsing the input of a two-phase solution.
+#[derive(Debug, Clone)]
+pub struct ParseResult {
+    pub day: Day,
+    /// Execution time of a single run. When benched, this is the median of all samples.
+    pub duration: Duration,
+    pub samples: u128,
+    /// Statistics of the benchmark, [`None`] if parsing was not benched.
+    pub stats: Option<BenchStats>,
+    /// Why parsing failed, [`None`] if it ran to completion.
+    pub error: Option<Failure>,
+}
+
+impl ParseResult {
+    /// Serializes the result to a single line of JSON, e.g.
+    /// `{"day":3,"phase":"parse","duration_nanos":166,"samples":1,"mean_nanos":null,...,"status":"parsed","error":null,...}`.
+    pub fn to_json(&self) -> String {
+        let mut fields = vec![
+            ("day", JsonValue::Number(f64::from(self.day.into_inner()))),
+            ("phase", JsonValue::String("parse".into())),
+        ];
+        fields.extend(timing_fields(
+            self.duration,
+            self.samples,
+            self.stats.as_ref(),
+        ));
+
+        let status = self.error.as_ref().map_or("parsed", Failure::as_str);
+        fields.push(("status", JsonValue::String(status.into())));
+        fields.extend(failure_fields(self.error.as_ref()));
+
+        json_line(&fields)
+    }
+
+    /// Reads a result written by [`ParseResult::to_json`].
+    pub(crate) fn from_record(record: &Record) -> Option<Self> {
+        if record.str("phase") != Some("parse") {
+            return None;
+        }
+
+        Some(Self {
+            day: record.day()?,
+            duration: record.duration("duration_nanos")?,
+            samples: record.number("samples")?,
+            stats: record.stats(),
+            error: record.failure(),
+        })
+    }
+}
+
+/// The outcome of running a whole day.
+#[derive(Debug, Clone)]
+pub struct DayResult {
+    /// [`None`] unless the solution parses its input in a separate phase.
+    pub parse: Option<ParseResult>,
+    pub parts: [PartResult; 2],
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn timing_fields(
+    duration: Duration,
+    samples: u128,
+    stats: Option<&BenchStats>,
+) -> Vec<(&'static str, JsonValue)> {
+    fn nanos(d: Duration) -> JsonValue {
+        JsonValue::Number(d.as_nanos() as f64)
+    }
+
+    let stat = |f: fn(&BenchStats) -> JsonValue| stats.map_or(JsonValue::Null, f);
+
+    vec![
+        ("duration_nanos", nanos(duration)),
+        ("samples", JsonValue::Number(samples as f64)),
+        ("mean_nanos", stat(|s| nanos(s.mean))),
+        ("min_nanos", stat(|s| nanos(s.min))),
+        ("max_nanos", stat(|s| nanos(s.max))),
+        ("p95_nanos", stat(|s| nanos(s.p95))),
+        ("stddev_nanos", stat(|s| nanos(s.stddev))),
+        ("outliers", stat(|s| JsonValue::Number(s.outliers as f64))),
+    ]
+}
+
+/// The message of a failure, the causes of an error and the limit that was exceeded,
+/// in nanoseconds for a timeout and in megabytes for memory.
+#[allow(clippy::cast_precision_loss)]
+fn failure_fields(error: Option<&Failure>) -> [(&'static str, JsonValue); 3] {
+    let causes = match error {
+        Some(Failure::Error(chain)) => JsonValue::Array(
+            chain
+                .iter()
+                .skip(1)
+                .map(|cause| JsonValue::String(cause.clone()))
+                .collect(),
+        ),
+        _ => JsonValue::Null,
+    };
+
+    let limit = match error {
+        Some(Failure::TimedOut(limit)) => JsonValue::Number(limit.as_nanos() as f64),
+        Some(Failure::OutOfMemory(limit)) => JsonValue::Number(*limit as f64),
+        _ => JsonValue::Null,
+    };
+
+    [
+        (
+            "error",
+            error.map_or(JsonValue::Null, |e| JsonValue::String(e.message())),
+        ),
+        ("causes", causes),
+        ("limit", limit),
+    ]
+}
+
+/// The fields of a JSON record, e.g. one written by [`PartResult::to_json`]. Fields that are `null` are missing.
+pub(crate) struct Record(HashMap<String, JsonValue>);
+
+impl Record {
+    pub(crate) fn parse(line: &str) -> Option<Self> {
+        let value: JsonValue = line.trim().parse().ok()?;
+        value.get::<HashMap<String, JsonValue>>().cloned().map(Self)
+    }
+
+    pub(crate) fn get(&self, key: &str) -> Option<&JsonValue> {
+        self.0.get(key).filter(|v| !matches!(v, JsonValue::Null))
+    }
+
+    pub(crate) fn str(&self, key: &str) -> Option<&str> {
+        self.get(key)?.get::<String>().map(String::as_str)
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub(crate) fn number(&self, key: &str) -> Option<u128> {
+        self.get(key)?.get::<f64>().map(|n| *n as u128)
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    pub(crate) fn duration(&self, key: &str) -> Option<Duration> {
+        self.number(key).map(|n| Duration::from_nanos(n as u64))
+    }
+
+    pub(crate) fn strings(&self, key: &str) -> Option<Vec<String>> {
+        self.get(key)?
+            .get::<Vec<JsonValue>>()?
+            .iter()
+            .map(|s| s.get::<String>().cloned())
+            .collect()
+    }
+
+    fn day(&self) -> Option<Day> {
+        Day::new(u8::try_from(self.number("day")?).ok()?)
+    }
+
+    /// The duration of a benched run is the median, the statistics cover the samples that weren't outliers.
+    fn stats(&self) -> Option<BenchStats> {
+        let outliers = self.number("outliers")?;
+        Some(BenchStats {
+            median: self.duration("duration_nanos")?,
+            mean: self.duration("mean_nanos")?,
+            min: self.duration("min_nanos")?,
+            max: self.duration("max_nanos")?,
+            p95: self.duration("p95_nanos")?,
+            stddev: self.duration("stddev_nanos")?,
+            samples: self.number("samples")?.saturating_sub(outliers),
+            outliers,
+        })
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn failure(&self) -> Option<Failure> {
+        let message = || self.str("error").map(str::to_string);
+
+        Some(match self.str("status")? {
+            "panicked" => Failure::Panicked(message()?),
+            "error" => {
+                let causes = self.strings("causes").unwrap_or_default();
+                let message = message()?;
+                let error = match causes.as_slice() {
+                    [] => message.as_str(),
+                    _ => message.strip_suffix(&format!(": {}", causes.join(": ")))?,
+                };
+                Failure::Error([vec![error.to_string()], causes].concat())
+            }
+            "timeout" => Failure::TimedOut(self.duration("limit")?),
+            "memory" => Failure::OutOfMemory(self.number("limit")? as u64),
+            _ => return None,
+        })
+    }
+}
+
+/// Fields are written by hand to keep a stable key order, values can't fail to stringify as they are all finite.
+pub(crate) fn json_line(fields: &[(&str, JsonValue)]) -> String {
+    let fields: Vec<String> = fields
+        .iter()
+        .map(|(key, value)| format!("\"{key}\":{}", value.stringify().unwrap()))
+        .collect();
+
+    format!("{{{}}}", fields.join(","))
+}
+
+thread_local! {
+    static PANIC_MESSAGE: RefCell<Option<String>> = const { RefCell::new(None) };
+    static IS_CATCHING: Cell<bool> = const { Cell::new(false) };
+}
+
+static PANIC_HOOK: Once = Once::new();
+
+/// Runs `f`, catching a panic instead of unwinding further.
+/// Returns the panic message and its location, e.g. `Invalid start at src/bin/2023-10.rs:45:13`, if `f` panicked.
+/// The default panic output is suppressed while `f` runs. The hook is installed once and only captures panics
+/// of threads inside `catch_panic`, so parts running on other threads don't interfere.
+pub(crate) fn catch_panic<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    PANIC_HOOK.call_once(|| {
+        let default = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !IS_CATCHING.with(Cell::get) {
+                default(info);
+                return;
+            }
+
+            let payload = info.payload();
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".into());
+
+            let message = match info.location() {
+                Some(location) => format!("{message} at {location}"),
+                None => message,
+            };
+
+            PANIC_MESSAGE.with(|m| *m.borrow_mut() = Some(message));
+        }));
+    });
+
+    let was_catching = IS_CATCHING.with(|c| c.replace(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    IS_CATCHING.with(|c| c.set(was_catching));
+
+    result.map_err(|_| {
+        PANIC_MESSAGE
+            .with(|m| m.borrow_mut().take())
+            .unwrap_or_else(|| "unknown panic".into())
+    })
+}
+
+/// Parse the input of a two-phase solution once, report the time it took and return the parsed input.
+/// Exits with an error if parsing panics, as neither part can run without the parsed input.
+pub fn run_parse<P>(func: impl Fn(&str) -> P, input: &str, day: Day) -> P {
+    match solve_parse(func, input, day, &RunOptions::from_args()).0 {
+        Some(parsed) => parsed,
+        None => process::exit(1),
+    }
+}
+
+/// Like [`run_parse`], but also returns the result of parsing. The parsed input is [`None`] if parsing panicked.
+pub fn solve_parse<P>(
+    func: impl Fn(&str) -> P,
+    input: &str,
+    day: Day,
+    options: &RunOptions,
+) -> (Option<P>, ParseResult) {
+    // parsing runs again, but quietly, when a part is resumed on a fresh worker.
+    let is_reported = limits::start(Stage::Parse);
+    let quiet;
+    let options = if is_reported {
+        options
+    } else {
+        quiet = RunOptions {
+            is_timed: false,
+            is_quiet: true,
+            ..options.clone()
+        };
+        &quiet
+    };
+    let is_human = !options.is_quiet && options.format == Format::Human;
+
+    let timed = options.params.apply(|| {
+        catch_panic(|| {
+            run_timed(func, input, options, |_| {
+                if is_human {
+                    print!("Parse:");
+                }
+            })
+        })
+    });
+
+    let (parsed, result) = match timed {
+        Ok((parsed, duration, samples, stats)) => (
+            Some(parsed),
+            ParseResult {
+                day,
+                duration,
+                samples,
+                stats,
+                error: None,
+            },
+        ),
+        Err(error) => (
+            None,
+            ParseResult {
+                day,
+                duration: Duration::ZERO,
+                samples: 0,
+                stats: None,
+                error: Some(Failure::Panicked(error)),
+            },
+        ),
+    };
+
+    if is_reported {
+        limits::finish_parse(&result);
+    }
+
+    if !options.is_quiet {
+        print_parse(&result, options.format);
+    }
+
+    (parsed, result)
+}
+
+pub(crate) fn print_parse(result: &ParseResult, format: Format) {
+    match format {
+        Format::Human => {
+            print!("\r");
+            match &result.error {
+                Some(error) => print_failure("Parse", error),
+                None => println!(
+                    "Parse:{}",
+                    format_duration(&result.duration, result.samples, result.stats.as_ref())
+                ),
+            }
+        }
+        Format::Json => write_record(&result.to_json()),
+    }
+}
+
+/// Run a solution part, report its output and submit the answer if `--submit` is passed.
+/// A panic or an error fails the part instead of aborting, so the next part still runs, see [`exit_on_failure`].
+pub fn run_part<I: Clone, R: PartOutput>(
+    func: impl Fn(I) -> R,
+    input: I,
+    day: Day,
+    part: u8,
+) -> PartResult {
+    let result = solve_part(func, input, day, part, &RunOptions::from_args());
+
+    if let Some(answer) = &result.answer {
+        submit_result(answer, day, part);
+    }
+
+    result
+}
+
+/// Warns about every `--param` that didn't match a parameter read by the solution, as it was ignored.
+pub fn warn_unmatched_params() {
+    for name in RunOptions::from_args().params.unmatched() {
+        eprintln!("Warning: --param {name} doesn't match any parameter read by the solution, it was ignored.");
+    }
+}
+
+/// Exits with an error if any of the parts failed.
+pub fn exit_on_failure(parts: &[PartResult]) {
+    if parts.iter().any(|p| p.error.is_some()) {
+        process::exit(1);
+    }
+}
+
+/// Run a solution part, report its output and return the result.
+/// Unlike [`run_part`], this never submits the answer, which makes it suitable for running many days in-process.
+pub fn solve_part<I: Clone, R: PartOutput>(
+    func: impl Fn(I) -> R,
+    input: I,
+    day: Day,
+    part: u8,
+    options: &RunOptions,
+) -> PartResult {
+    if options.part.is_some_and(|p| p != part) || !limits::start(Stage::Part(part)) {
+        return PartResult::skipped(day, part);
+    }
+
     let part_str = format!("Part {part}");
+    let is_human = !options.is_quiet && options.format == Format::Human;
+
+    let timed = options.params.apply(|| {
+        catch_panic(|| {
+            run_timed(func, input, options, |output| {
+                if is_human {
+                    print_result(&output.answer(), &part_str, "");
+                }
+            })
+        })
+    });
+
+    let mut result = match timed {
+        Ok((output, duration, samples, stats)) => {
+            let (answer, error) = match output.into_answer() {
+                Ok(answer) => (answer, None),
+                Err(error) => (None, Some(error)),
+            };
 
-    let (result, duration, samples) =
-        run_timed(func, input, |result| print_result(result, &part_str, ""));
+            PartResult {
+                day,
+                part,
+                answer,
+                duration,
+                samples,
+                stats,
+                verdict: None,
+                error,
+            }
+        }
+        Err(error) => PartResult::failed(day, part, Failure::Panicked(error)),
+    };
 
-    print_result(&result, &part_str, &format_duration(&duration, samples));
+    result.verdict = options
+        .verify
+        .then(|| answers::verify(day, part, result.answer.as_deref()));
 
-    if let Some(result) = result {
-        submit_result(result, day, part);
+    limits::finish_part(&result);
+
+    if !options.is_quiet {
+        print_part(&result, options.format);
+    }
+
+    result
+}
+
+pub(crate) fn print_part(result: &PartResult, format: Format) {
+    match format {
+        Format::Human => {
+            let part_str = format!("Part {}", result.part);
+            match &result.error {
+                Some(error) => {
+                    print!("\r");
+                    print_failure(&part_str, error);
+                }
+                None => print_result(
+                    &result.answer,
+                    &part_str,
+                    &format_duration(&result.duration, result.samples, result.stats.as_ref()),
+                ),
+            }
+            if let Some(verdict) = &result.verdict {
+                println!("  {ANSI_ITALIC}{verdict}{ANSI_RESET}");
+            }
+        }
+        Format::Json => write_record(&result.to_json()),
     }
 }
 
-/// Run a solution part. The behavior differs depending on whether we are running a release or debug build:
-///  1. in debug, the function is executed once.
-///  2. in release, the function is benched (approx. 1 second of execution time or 10 samples, whatever take longer.)
+/// Run a solution part. The behavior differs depending on whether the `--time` flag is passed:
+///  1. without, the function is executed once.
+///  2. with, the function is warmed up and then benched (approx. 1 second of execution time or 10 samples, whatever take longer.)
 fn run_timed<I: Clone, T>(
     func: impl Fn(I) -> T,
     input: I,
+    options: &RunOptions,
     hook: impl Fn(&T),
-) -> (T, Duration, u128) {
+) -> (T, Duration, u128, Option<BenchStats>) {
     let timer = Instant::now();
     let result = func(input.clone());
     let base_time = timer.elapsed();
 
+    limits::ran(base_time);
     hook(&result);
 
-    let run = if std::env::args().any(|x| x == "--time") {
-        bench(func, input, &base_time)
-    } else {
-        (base_time, 1)
-    };
+    if !options.is_timed {
+        return (result, base_time, 1, None);
+    }
 
-    (result, run.0, run.1)
+    let show_progress = !options.is_quiet && options.format == Format::Human;
+    match bench(func, input, base_time, &options.bench, show_progress) {
+        Some((stats, samples)) => (result, stats.median, samples, Some(stats)),
+        None => (result, base_time, 1, None),
+    }
 }
 
-fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> (Duration, u128) {
-    let mut stdout = stdout();
+fn bench<I: Clone, T>(
+    func: impl Fn(I) -> T,
+    input: I,
+    base_time: Duration,
+    config: &BenchConfig,
+    show_progress: bool,
+) -> Option<(BenchStats, u128)> {
+    if show_progress {
+        print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
+        let _ = stdout().flush();
+    }
 
-    print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
-    let _ = stdout.flush();
+    // the first run counts towards the warm-up, its duration also serves as the estimate if there is no further warm-up.
+    let warmup = Instant::now();
+    let mut warmup_runs = 1;
+    while base_time + warmup.elapsed() < config.warmup_time {
+        func(input.clone());
+        warmup_runs += 1;
+    }
+    let estimate = (base_time + warmup.elapsed()) / warmup_runs;
 
-    let bench_iterations = cmp::min(
-        10000,
-        cmp::max(
-            Duration::from_secs(1).as_nanos() / cmp::max(base_time.as_nanos(), 10),
-            10,
-        ),
-    );
+    let bench_iterations = config.iterations_for(estimate);
 
     let mut timers: Vec<Duration> = vec![];
 
@@ -69,27 +956,29 @@ fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) ->
         timers.push(timer.elapsed());
     }
 
-    (
-        #[allow(clippy::cast_possible_truncation)]
-        Duration::from_nanos(average_duration(&timers) as u64),
-        bench_iterations,
-    )
+    BenchStats::from_samples(&timers).map(|stats| (stats, bench_iterations))
 }
 
-fn average_duration(numbers: &[Duration]) -> u128 {
-    numbers
-        .iter()
-        .map(std::time::Duration::as_nanos)
-        .sum::<u128>()
-        / numbers.len() as u128
+fn format_duration(duration: &Duration, samples: u128, stats: Option<&BenchStats>) -> String {
+    match stats {
+        None if samples == 1 => format!(" ({duration:.1?})"),
+        None => format!(" ({duration:.1?} @ {samples} samples)"),
+        Some(stats) => {
+            let outliers = match stats.outliers {
+                0 => String::new(),
+                1 => ", 1 outlier".into(),
+                n => format!(", {n} outliers"),
+            };
+            format!(
+                " ({duration:.1?} @ {samples} samples; min {:.1?}, max {:.1?}, p95 {:.1?}, σ {:.1?}{outliers})",
+                stats.min, stats.max, stats.p95, stats.stddev
+            )
+        }
+    }
 }
 
-fn format_duration(duration: &Duration, samples: u128) -> String {
-    if samples == 1 {
-        format!(" ({duration:.1?})")
-    } else {
-        format!(" ({duration:.1?} @ {samples} samples)")
-    }
+fn print_failure(part: &str, failure: &Failure) {
+    println!("{part}: ✖ {ANSI_ITALIC}{failure}{ANSI_RESET}");
 }
 
 fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str) {
@@ -129,12 +1018,16 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
 
 /// Parse the arguments passed to `solve` and try to submit one part of the solution if:
 ///  1. we are in `--release` mode.
-///  2. aoc-cli is installed.
-fn submit_result<T: Display>(
+///  2. a session cookie is configured.
+///  3. the answer is not refused based on earlier submissions, unless `--force` is passed.
+///
+/// Every recognized outcome is appended to the day's submission log.
+/// If the answer is accepted, it is recorded in `data/answers` for later verification.
+pub(crate) fn submit_result<T: Display>(
     result: T,
     day: Day,
     part: u8,
-) -> Option<Result<Output, aoc_cli::AocCommandError>> {
+) -> Option<Result<Option<SubmissionOutcome>, AocClientError>> {
     let args: Vec<String> = env::args().collect();
 
     if !args.contains(&"--submit".into()) {
@@ -157,11 +1050,176 @@ fn submit_result<T: Display>(
         return None;
     }
 
-    if aoc_cli::check().is_err() {
-        eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
-        process::exit(1);
+    let result = result.to_string();
+
+    if !args.contains(&"--force".into()) {
+        if let Err(refusal) = submissions::check(&submissions::read_log(day), part, &result) {
+            eprintln!("Refusing to submit: {refusal}. Pass --force to submit anyway.");
+            return None;
+        }
     }
 
-    println!("Submitting result via aoc-cli...");
-    Some(aoc_cli::submit(day, part, &result.to_string()))
+    let client = match AocClient::from_env() {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("Could not submit: {e}");
+            process::exit(1);
+        }
+    };
+
+    println!("Submitting result...");
+    let response = match client.submit(day, part, &result) {
+        Ok(response) => aoc_client::strip_tags(&response),
+        Err(e) => {
+            eprintln!("Failed to submit answer: {e}");
+            return Some(Err(e));
+        }
+    };
+
+    let Some(outcome) = SubmissionOutcome::parse(&response) else {
+        println!("Unrecognized response:\n{response}");
+        return Some(Ok(None));
+    };
+
+    println!("{outcome}");
+
+    let submission = Submission::new(part, &result, outcome, &response);
+    if let Err(e) = submissions::append_log(day, &submission) {
+        eprintln!("Failed to log submission: {e}");
+    }
+
+    if outcome == SubmissionOutcome::Correct {
+        match answers::write(day, part, &result) {
+            Ok(()) => println!("🎄 Recorded answer for day {day}, part {part}."),
+            Err(e) => eprintln!("Failed to record answer: {e}"),
+        }
+    }
+
+    Some(Ok(Some(outcome)))
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    use tinyjson::JsonValue;
+
+    use super::{solve_part, Failure, Format, PartOutput, PartResult, RunOptions, Unsolved};
+    use crate::day;
+    use crate::template::answers::Verdict;
+
+    fn parse(result: &PartResult) -> HashMap<String, JsonValue> {
+        let json: JsonValue = result.to_json().parse().unwrap();
+        json.get::<HashMap<String, JsonValue>>().unwrap().clone()
+    }
+
+    #[test]
+    fn test_json_record() {
+        let record = parse(&PartResult {
+            day: day!(3),
+            part: 2,
+            answer: Some("42 samples)\nfoo".into()),
+            duration: Duration::from_nanos(1500),
+            samples: 10,
+            stats: None,
+            verdict: Some(Verdict::Pass),
+            error: None,
+        });
+        assert_eq!(record["day"], JsonValue::Number(3.0));
+        assert_eq!(record["part"], JsonValue::Number(2.0));
+        assert_eq!(
+            record["answer"],
+            JsonValue::String("42 samples)\nfoo".into())
+        );
+        assert_eq!(record["duration_nanos"], JsonValue::Number(1500.0));
+        assert_eq!(record["samples"], JsonValue::Number(10.0));
+        assert_eq!(record["status"], JsonValue::String("solved".into()));
+        assert_eq!(record["verdict"], JsonValue::String("pass".into()));
+    }
+
+    #[test]
+    fn test_json_record_unsolved() {
+        let record = parse(&PartResult {
+            day: day!(3),
+            part: 1,
+            answer: None,
+            duration: Duration::ZERO,
+            samples: 1,
+            stats: None,
+            verdict: None,
+            error: None,
+        });
+        assert_eq!(record["answer"], JsonValue::Null);
+        assert_eq!(record["status"], JsonValue::String("unsolved".into()));
+        assert_eq!(record["verdict"], JsonValue::Null);
+        assert_eq!(record["error"], JsonValue::Null);
+    }
+
+    #[test]
+    fn test_solve_part_catches_panic() {
+        let options = RunOptions {
+            format: Format::Json,
+            ..RunOptions::default()
+        };
+
+        let result = solve_part(
+            |_: &str| -> Option<u32> { panic!("Invalid start") },
+            "",
+            day!(10),
+            1,
+            &options,
+        );
+        let Some(Failure::Panicked(error)) = &result.error else {
+            panic!("expected a panic, got {:?}", result.error);
+        };
+        assert!(error.starts_with("Invalid start at src/template/runner.rs:"));
+        assert_eq!(result.status(), "panicked");
+
+        let record = parse(&result);
+        assert_eq!(record["status"], JsonValue::String("panicked".into()));
+        assert_eq!(record["error"], JsonValue::String(error.clone()));
+    }
+
+    #[derive(Debug)]
+    struct ParseError(std::num::ParseIntError);
+
+    impl std::fmt::Display for ParseError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str("invalid seed")
+        }
+    }
+
+    impl std::error::Error for ParseError {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    #[test]
+    fn test_part_output() {
+        assert_eq!(Some(42).into_answer(), Ok(Some("42".into())));
+        assert_eq!(None::<u32>.into_answer(), Ok(None));
+        assert_eq!(42_u64.into_answer(), Ok(Some("42".into())));
+        assert_eq!(Ok::<_, &str>("ok").into_answer(), Ok(Some("ok".into())));
+        assert_eq!(
+            Err::<u64, _>("Position not in map").into_answer(),
+            Err(Failure::Error(vec!["Position not in map".into()]))
+        );
+        assert_eq!(Err::<u64, _>(Unsolved).into_answer(), Ok(None));
+
+        let error = "x"
+            .parse::<u32>()
+            .map_err(ParseError)
+            .into_answer()
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "error: invalid seed\n  caused by: invalid digit found in string"
+        );
+        assert_eq!(
+            error.message(),
+            "invalid seed: invalid digit found in string"
+        );
+    }
 }
@@ -0,0 +1,484 @@
+//! Module that interprets the server's response to a submitted answer and keeps a log of all submissions.
+//! Submissions are appended to `data/YYYY/submissions/NN.jsonl`, one JSON record per line.
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use tinyjson::JsonValue;
+
+use crate::template::{data_dir, runner::json_line};
+use crate::Day;
+
+/// Outcome of a submitted answer, as reported by the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubmissionOutcome {
+    Correct,
+    Wrong,
+    TooHigh,
+    TooLow,
+    /// The answer was submitted too soon after the previous one and has not been checked.
+    Wait {
+        seconds: u64,
+    },
+    /// The part has already been solved, the answer has not been checked.
+    AlreadySolved,
+}
+
+impl SubmissionOutcome {
+    /// Parses the text of a response, returns [`None`] if the response is not recognized.
+    pub fn parse(response: &str) -> Option<Self> {
+        if response.contains("That's the right answer") {
+            Some(Self::Correct)
+        } else if response.contains("That's not the right answer") {
+            if response.contains("your answer is too high") {
+                Some(Self::TooHigh)
+            } else if response.contains("your answer is too low") {
+                Some(Self::TooLow)
+            } else {
+                Some(Self::Wrong)
+            }
+        } else if response.contains("You gave an answer too recently") {
+            Some(Self::Wait {
+                seconds: parse_wait(response).unwrap_or(0),
+            })
+        } else if response.contains("Did you already complete it?") {
+            Some(Self::AlreadySolved)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Correct => "correct",
+            Self::Wrong => "wrong",
+            Self::TooHigh => "too_high",
+            Self::TooLow => "too_low",
+            Self::Wait { .. } => "wait",
+            Self::AlreadySolved => "already_solved",
+        }
+    }
+
+    fn from_record(s: &str, seconds: Option<u64>) -> Option<Self> {
+        match s {
+            "correct" => Some(Self::Correct),
+            "wrong" => Some(Self::Wrong),
+            "too_high" => Some(Self::TooHigh),
+            "too_low" => Some(Self::TooLow),
+            "wait" => Some(Self::Wait {
+                seconds: seconds.unwrap_or(0),
+            }),
+            "already_solved" => Some(Self::AlreadySolved),
+            _ => None,
+        }
+    }
+}
+
+impl Display for SubmissionOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Correct => write!(f, "✔ That's the right answer!"),
+            Self::Wrong => write!(f, "✖ That's not the right answer."),
+            Self::TooHigh => write!(f, "✖ That's not the right answer, it is too high."),
+            Self::TooLow => write!(f, "✖ That's not the right answer, it is too low."),
+            Self::Wait { seconds } => write!(
+                f,
+                "⏳ You gave an answer too recently, wait {seconds}s before trying again."
+            ),
+            Self::AlreadySolved => write!(f, "✔ This part has already been solved."),
+        }
+    }
+}
+
+/// Parses durations like "1m 5s" in "You have 1m 5s left to wait."
+fn parse_wait(response: &str) -> Option<u64> {
+    let end = response.find(" left to wait")?;
+    let start = response[..end].rfind("You have ")? + "You have ".len();
+
+    response[start..end]
+        .split_whitespace()
+        .map(|token| {
+            let (value, unit) = token.split_at(token.len() - 1);
+            let value: u64 = value.parse().ok()?;
+            match unit {
+                "h" => Some(value * 3600),
+                "m" => Some(value * 60),
+                "s" => Some(value),
+                _ => None,
+            }
+        })
+        .sum()
+}
+
+/// Parses the delay after a wrong answer, like "Please wait one minute before trying again."
+fn parse_retry(response: &str) -> Option<u64> {
+    let response = response.to_lowercase();
+    let start = response.find("please wait ")? + "please wait ".len();
+    let mut words = response[start..].split_whitespace();
+
+    let count: u64 = match words.next()? {
+        "one" | "a" => 1,
+        n => n.parse().ok()?,
+    };
+
+    match words.next()? {
+        unit if unit.starts_with("second") => Some(count),
+        unit if unit.starts_with("minute") => Some(count * 60),
+        unit if unit.starts_with("hour") => Some(count * 3600),
+        _ => None,
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// A submitted answer and the server's verdict.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Submission {
+    /// Seconds since the unix epoch.
+    pub timestamp: u64,
+    pub part: u8,
+    pub answer: String,
+    pub outcome: SubmissionOutcome,
+    /// Seconds the server asked to wait before submitting again.
+    pub cooldown: Option<u64>,
+}
+
+impl Submission {
+    /// Creates a submission made just now from the text of the server's response.
+    pub fn new(part: u8, answer: &str, outcome: SubmissionOutcome, response: &str) -> Self {
+        let cooldown = match outcome {
+            SubmissionOutcome::Wait { seconds } => Some(seconds),
+            _ => parse_retry(response),
+        };
+
+        Self {
+            timestamp: now(),
+            part,
+            answer: answer.to_string(),
+            outcome,
+            cooldown,
+        }
+    }
+
+    /// Whether the server checked the answer, as opposed to rejecting the submission.
+    fn is_checked(&self) -> bool {
+        !matches!(
+            self.outcome,
+            SubmissionOutcome::Wait { .. } | SubmissionOutcome::AlreadySolved
+        )
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn to_json(&self) -> String {
+        let wait = self
+            .cooldown
+            .map_or(JsonValue::Null, |x| JsonValue::Number(x as f64));
+
+        json_line(&[
+            ("timestamp", JsonValue::Number(self.timestamp as f64)),
+            ("part", JsonValue::Number(f64::from(self.part))),
+            ("answer", JsonValue::String(self.answer.clone())),
+            ("outcome", JsonValue::String(self.outcome.as_str().into())),
+            ("wait_seconds", wait),
+        ])
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn parse(line: &str) -> Option<Self> {
+        let json: JsonValue = line.parse().ok()?;
+        let fields = json.get::<HashMap<String, JsonValue>>()?;
+        let number = |key: &str| fields.get(key)?.get::<f64>().copied();
+        let string = |key: &str| fields.get(key)?.get::<String>().cloned();
+        let cooldown = number("wait_seconds").map(|x| x as u64);
+
+        Some(Self {
+            timestamp: number("timestamp")? as u64,
+            part: number("part")? as u8,
+            answer: string("answer")?,
+            outcome: SubmissionOutcome::from_record(&string("outcome")?, cooldown)?,
+            cooldown,
+        })
+    }
+}
+
+fn get_log_path(day: Day) -> PathBuf {
+    data_dir("submissions").join(format!("{day}.jsonl"))
+}
+
+/// Reads all submissions for a day, oldest first. Lines that can't be parsed are skipped.
+pub fn read_log(day: Day) -> Vec<Submission> {
+    fs::read_to_string(get_log_path(day))
+        .map(|log| log.lines().filter_map(Submission::parse).collect())
+        .unwrap_or_default()
+}
+
+/// Appends a submission to the log of a day.
+pub fn append_log(day: Day, submission: &Submission) -> io::Result<()> {
+    let path = get_log_path(day);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", submission.to_json())
+}
+
+/// Reason for refusing to submit an answer, based on earlier submissions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Refusal {
+    /// The same answer was already checked by the server.
+    Duplicate { outcome: SubmissionOutcome },
+    /// The part was already answered correctly.
+    Solved { answer: String },
+    /// An answer this high or higher was already too high.
+    AboveBound { bound: String },
+    /// An answer this low or lower was already too low.
+    BelowBound { bound: String },
+    /// The server asked to wait before submitting again.
+    Cooldown { seconds: u64 },
+}
+
+impl Display for Refusal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Refusal::Duplicate { outcome } => write!(
+                f,
+                "this answer was already submitted, the outcome was: {}",
+                outcome.as_str()
+            ),
+            Refusal::Solved { answer } => {
+                write!(f, "this part was already solved with answer {answer}")
+            }
+            Refusal::AboveBound { bound } => {
+                write!(f, "the answer {bound} was already too high")
+            }
+            Refusal::BelowBound { bound } => {
+                write!(f, "the answer {bound} was already too low")
+            }
+            Refusal::Cooldown { seconds } => {
+                write!(f, "the server asked to wait, {seconds}s left")
+            }
+        }
+    }
+}
+
+/// Checks whether `answer` may be submitted for a part, given the submission history of its day.
+pub fn check(history: &[Submission], part: u8, answer: &str) -> Result<(), Refusal> {
+    check_at(history, part, answer, now())
+}
+
+fn check_at(history: &[Submission], part: u8, answer: &str, now: u64) -> Result<(), Refusal> {
+    // the server enforces the delay for a whole day, regardless of the part.
+    if let Some(until) = history
+        .iter()
+        .filter_map(|s| Some(s.timestamp + s.cooldown?))
+        .max()
+    {
+        if until > now {
+            return Err(Refusal::Cooldown {
+                seconds: until - now,
+            });
+        }
+    }
+
+    let checked: Vec<&Submission> = history
+        .iter()
+        .filter(|s| s.part == part && s.is_checked())
+        .collect();
+
+    if let Some(correct) = checked
+        .iter()
+        .find(|s| s.outcome == SubmissionOutcome::Correct)
+    {
+        return Err(Refusal::Solved {
+            answer: correct.answer.clone(),
+        });
+    }
+
+    if let Some(previous) = checked.iter().find(|s| s.answer == answer) {
+        return Err(Refusal::Duplicate {
+            outcome: previous.outcome,
+        });
+    }
+
+    let Ok(value) = answer.parse::<i128>() else {
+        return Ok(());
+    };
+
+    let bound = |outcome: SubmissionOutcome| {
+        checked
+            .iter()
+            .filter(move |s| s.outcome == outcome)
+            .filter_map(|s| Some((s.answer.parse::<i128>().ok()?, s)))
+    };
+
+    if let Some((_, s)) = bound(SubmissionOutcome::TooHigh)
+        .filter(|(high, _)| value >= *high)
+        .min_by_key(|(high, _)| *high)
+    {
+        return Err(Refusal::AboveBound {
+            bound: s.answer.clone(),
+        });
+    }
+
+    if let Some((_, s)) = bound(SubmissionOutcome::TooLow)
+        .filter(|(low, _)| value <= *low)
+        .max_by_key(|(low, _)| *low)
+    {
+        return Err(Refusal::BelowBound {
+            bound: s.answer.clone(),
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{check_at, Refusal, Submission, SubmissionOutcome};
+
+    #[test]
+    fn test_parse_outcomes() {
+        let cases = [
+            ("That's the right answer! You are one gold star closer.", SubmissionOutcome::Correct),
+            ("That's not the right answer. If you're stuck, make sure you're using the full input data.", SubmissionOutcome::Wrong),
+            ("That's not the right answer; your answer is too high. Please wait one minute before trying again.", SubmissionOutcome::TooHigh),
+            ("That's not the right answer; your answer is too low.", SubmissionOutcome::TooLow),
+            ("You don't seem to be solving the right level.  Did you already complete it?", SubmissionOutcome::AlreadySolved),
+        ];
+
+        for (response, outcome) in cases {
+            assert_eq!(SubmissionOutcome::parse(response), Some(outcome));
+        }
+
+        assert_eq!(SubmissionOutcome::parse("Something else."), None);
+    }
+
+    #[test]
+    fn test_parse_wait() {
+        let response = "You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 1m 5s left to wait.";
+        assert_eq!(
+            SubmissionOutcome::parse(response),
+            Some(SubmissionOutcome::Wait { seconds: 65 })
+        );
+
+        let response = "You gave an answer too recently. You have 32s left to wait.";
+        assert_eq!(
+            SubmissionOutcome::parse(response),
+            Some(SubmissionOutcome::Wait { seconds: 32 })
+        );
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        for (outcome, cooldown) in [
+            (SubmissionOutcome::TooLow, None),
+            (SubmissionOutcome::Wrong, Some(60)),
+            (SubmissionOutcome::Wait { seconds: 30 }, Some(30)),
+        ] {
+            let submission = Submission {
+                timestamp: 1_700_000_000,
+                part: 2,
+                answer: "4\"2".into(),
+                outcome,
+                cooldown,
+            };
+            assert_eq!(Submission::parse(&submission.to_json()), Some(submission));
+        }
+    }
+
+    fn submission(
+        timestamp: u64,
+        part: u8,
+        answer: &str,
+        outcome: SubmissionOutcome,
+    ) -> Submission {
+        Submission {
+            timestamp,
+            part,
+            answer: answer.into(),
+            outcome,
+            cooldown: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_retry() {
+        let response = "That's not the right answer. Please wait one minute before trying again.";
+        let submission = Submission::new(1, "1", SubmissionOutcome::Wrong, response);
+        assert_eq!(submission.cooldown, Some(60));
+
+        let response = "Because you have guessed incorrectly 4 times on this puzzle, please wait 5 minutes before trying again.";
+        let submission = Submission::new(1, "1", SubmissionOutcome::Wrong, response);
+        assert_eq!(submission.cooldown, Some(300));
+    }
+
+    #[test]
+    fn test_check_duplicates() {
+        let history = [
+            submission(0, 1, "42", SubmissionOutcome::Wrong),
+            submission(0, 2, "7", SubmissionOutcome::Wait { seconds: 0 }),
+        ];
+        assert_eq!(
+            check_at(&history, 1, "42", 100),
+            Err(Refusal::Duplicate {
+                outcome: SubmissionOutcome::Wrong
+            })
+        );
+        assert_eq!(check_at(&history, 2, "42", 100), Ok(()));
+        // the server did not check answers that were submitted too early.
+        assert_eq!(check_at(&history, 2, "7", 100), Ok(()));
+    }
+
+    #[test]
+    fn test_check_solved() {
+        let history = [submission(0, 1, "42", SubmissionOutcome::Correct)];
+        assert_eq!(
+            check_at(&history, 1, "43", 100),
+            Err(Refusal::Solved {
+                answer: "42".into()
+            })
+        );
+    }
+
+    #[test]
+    fn test_check_bounds() {
+        let history = [
+            submission(0, 1, "100", SubmissionOutcome::TooHigh),
+            submission(0, 1, "80", SubmissionOutcome::TooHigh),
+            submission(0, 1, "20", SubmissionOutcome::TooLow),
+        ];
+        assert_eq!(
+            check_at(&history, 1, "90", 100),
+            Err(Refusal::AboveBound { bound: "80".into() })
+        );
+        assert_eq!(
+            check_at(&history, 1, "5", 100),
+            Err(Refusal::BelowBound { bound: "20".into() })
+        );
+        assert_eq!(check_at(&history, 1, "50", 100), Ok(()));
+        assert_eq!(check_at(&history, 1, "abc", 100), Ok(()));
+        assert_eq!(check_at(&history, 2, "90", 100), Ok(()));
+    }
+
+    #[test]
+    fn test_check_cooldown() {
+        let mut wrong = submission(1_000, 1, "42", SubmissionOutcome::Wrong);
+        wrong.cooldown = Some(60);
+        let history = [wrong];
+        assert_eq!(
+            check_at(&history, 2, "1", 1_045),
+            Err(Refusal::Cooldown { seconds: 15 })
+        );
+        assert_eq!(check_at(&history, 2, "1", 1_060), Ok(()));
+    }
+}
@@ -0,0 +1,131 @@
+use std::error::Error;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// A year of advent of code (i.e. an integer from 2015, the first event, to 9999).
+///
+/// # Display
+/// This value displays as a four digit number.
+///
+/// ```
+/// # use advent_of_code::Year;
+/// let year = Year::new(2023).unwrap();
+/// assert_eq!(year.to_string(), "2023")
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Year(u16);
+
+impl Year {
+    /// Creates a [`Year`] from the provided value if it's in the valid range,
+    /// returns [`None`] otherwise.
+    pub fn new(year: u16) -> Option<Self> {
+        if !(2015..=9999).contains(&year) {
+            return None;
+        }
+        Some(Self(year))
+    }
+
+    // Not part of the public API
+    #[doc(hidden)]
+    pub const fn __new_unchecked(year: u16) -> Self {
+        Self(year)
+    }
+
+    /// Converts the [`Year`] into an [`u16`].
+    pub fn into_inner(self) -> u16 {
+        self.0
+    }
+
+    /// Reads the year from the name of a solution binary, e.g. `2023-01`.
+    /// Test executables are named after the crate, which is why `2023_01-<hash>` is accepted as well.
+    pub fn from_bin_name(name: &str) -> Option<Self> {
+        let (year, rest) = name.split_at_checked(4)?;
+        let rest = rest.strip_prefix(['-', '_'])?;
+        let is_day = rest.len() >= 2 && rest.as_bytes()[..2].iter().all(u8::is_ascii_digit);
+
+        if !is_day || !year.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+
+        Self::new(year.parse().ok()?)
+    }
+}
+
+impl Display for Year {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl PartialEq<u16> for Year {
+    fn eq(&self, other: &u16) -> bool {
+        self.0.eq(other)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl FromStr for Year {
+    type Err = YearFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let year = s.parse().map_err(|_| YearFromStrError)?;
+        Self::new(year).ok_or(YearFromStrError)
+    }
+}
+
+/// An error which can be returned when parsing a [`Year`].
+#[derive(Debug)]
+pub struct YearFromStrError;
+
+impl Error for YearFromStrError {}
+
+impl Display for YearFromStrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("expecting a year of advent of code, i.e. 2015 or later")
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Creates a [`Year`] value in a const context.
+#[macro_export]
+macro_rules! year {
+    ($year:expr) => {{
+        const _ASSERT: () = assert!(
+            $year >= 2015 && $year <= 9999,
+            concat!(
+                "invalid year `",
+                $year,
+                "`, expecting a value between 2015 and 9999"
+            ),
+        );
+        $crate::Year::__new_unchecked($year)
+    }};
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Year;
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("2023".parse::<Year>().unwrap(), Year(2023));
+        assert!("2014".parse::<Year>().is_err());
+        assert!("23".parse::<Year>().is_err());
+    }
+
+    #[test]
+    fn test_from_bin_name() {
+        assert_eq!(Year::from_bin_name("2023-01"), Some(Year(2023)));
+        assert_eq!(
+            Year::from_bin_name("2015_25-0123456789abcdef"),
+            Some(Year(2015))
+        );
+        assert_eq!(Year::from_bin_name("01"), None);
+        assert_eq!(Year::from_bin_name("advent_of_code"), None);
+        assert_eq!(Year::from_bin_name("2023-xx"), None);
+    }
+}